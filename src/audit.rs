@@ -0,0 +1,201 @@
+use std::{collections::BTreeMap, fmt};
+
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_normalization::is_nfc;
+use unicode_script::{Script, UnicodeScript};
+
+use crate::{compose::compose_strict, kbdwriter::KbdWriter};
+
+/// Code points with the Unicode `Deprecated` property
+const DEPRECATED: [char; 15] = [
+    '\u{149}',
+    '\u{673}',
+    '\u{f77}',
+    '\u{f79}',
+    '\u{17a3}',
+    '\u{17a4}',
+    '\u{206a}',
+    '\u{206b}',
+    '\u{206c}',
+    '\u{206d}',
+    '\u{206e}',
+    '\u{206f}',
+    '\u{2329}',
+    '\u{232a}',
+    '\u{e0001}',
+];
+
+#[derive(Debug, PartialEq)]
+pub enum Issue {
+    Unassigned(char),
+    Deprecated(char),
+    NotNfc,
+    LooseMarks(Vec<char>),
+    /// Letters outside the script of the rest of the layout
+    MixedScripts(Vec<Script>),
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Unassigned(c) => write!(f, "unassigned code point U+{:04X}", *c as u32),
+            Issue::Deprecated(c) => write!(f, "deprecated code point U+{:04X}", *c as u32),
+            Issue::NotNfc => write!(f, "not in NFC"),
+            Issue::LooseMarks(marks) => {
+                let marks: Vec<String> = marks
+                    .iter()
+                    .map(|c| format!("U+{:04X}", *c as u32))
+                    .collect();
+                write!(f, "loose combining marks {}", marks.join(" "))
+            }
+            Issue::MixedScripts(scripts) => {
+                let scripts: Vec<&str> = scripts.iter().map(|s| s.full_name()).collect();
+                write!(f, "mixed scripts {}", scripts.join(", "))
+            }
+        }
+    }
+}
+
+/// The scripts of the letters in a string, ignoring punctuation and marks
+fn scripts(str: &str) -> Vec<Script> {
+    let mut scripts: Vec<Script> = str
+        .chars()
+        .map(|c| c.script())
+        .filter(|script| !matches!(script, Script::Common | Script::Inherited | Script::Unknown))
+        .collect();
+    scripts.sort_by_key(|script| script.full_name());
+    scripts.dedup();
+    scripts
+}
+
+/// The script most outputs of the layout are written in
+fn main_script(kbd: &KbdWriter) -> Option<Script> {
+    let mut counts: BTreeMap<&str, (Script, usize)> = BTreeMap::new();
+    for (_, keymap) in kbd.sections.iter() {
+        for (_, mapped) in keymap.iter() {
+            for script in scripts(mapped) {
+                counts.entry(script.full_name()).or_insert((script, 0)).1 += 1;
+            }
+        }
+    }
+    counts
+        .into_values()
+        .max_by_key(|(_, count)| *count)
+        .map(|(script, _)| script)
+}
+
+/// Checks one output, `script` is the script the layout is written in and `extra` the
+/// other scripts it may use
+pub fn check(mapped: &str, script: Option<Script>, extra: &[Script]) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for c in mapped.chars() {
+        if get_general_category(c) == GeneralCategory::Unassigned {
+            issues.push(Issue::Unassigned(c));
+        }
+        if DEPRECATED.contains(&c) {
+            issues.push(Issue::Deprecated(c));
+        }
+    }
+    if !is_nfc(mapped) {
+        issues.push(Issue::NotNfc);
+    }
+    if let Err(loose) = compose_strict(mapped.chars().collect()) {
+        issues.push(Issue::LooseMarks(loose.0));
+    }
+    let scripts = scripts(mapped);
+    if scripts
+        .iter()
+        .any(|s| Some(*s) != script && !extra.contains(s))
+    {
+        issues.push(Issue::MixedScripts(scripts));
+    }
+    issues
+}
+
+pub struct SectionReport<'a> {
+    pub section: &'a String,
+    pub mappings: usize,
+    /// Key sequence, output and what is wrong with it
+    pub issues: Vec<(&'a String, &'a String, Issue)>,
+}
+
+/// Every issue of every output, grouped by section. `extra` are the scripts the layout
+/// uses besides its main one
+pub fn audit<'a>(kbd: &'a KbdWriter, extra: &[Script]) -> Vec<SectionReport<'a>> {
+    let script = main_script(kbd);
+    kbd.sections
+        .iter()
+        .map(|(section, keymap)| {
+            let mut report = SectionReport {
+                section,
+                mappings: 0,
+                issues: Vec::new(),
+            };
+            for (seq, mapped) in keymap.iter() {
+                report.mappings += 1;
+                for issue in check(mapped, script, extra) {
+                    report.issues.push((seq, mapped, issue));
+                }
+            }
+            report
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn clean() {
+        assert!(check("ἄ", Some(Script::Greek), &[]).is_empty());
+        assert!(check("«", Some(Script::Greek), &[]).is_empty());
+    }
+    #[test]
+    fn issues() {
+        assert_eq!(
+            check("\u{3b1}\u{301}", Some(Script::Greek), &[]),
+            vec![Issue::NotNfc]
+        );
+        assert_eq!(
+            check("ῡ\u{308}", Some(Script::Greek), &[]),
+            vec![Issue::LooseMarks(vec!['\u{308}'])]
+        );
+        assert_eq!(
+            check("\u{378}", None, &[]),
+            vec![Issue::Unassigned('\u{378}')]
+        );
+        assert_eq!(
+            check("ŉ", Some(Script::Latin), &[]),
+            vec![Issue::Deprecated('ŉ')]
+        );
+        assert_eq!(
+            check("a", Some(Script::Greek), &[]),
+            vec![Issue::MixedScripts(vec![Script::Latin])]
+        );
+    }
+    #[test]
+    fn extra_scripts() {
+        assert!(check("θ", Some(Script::Latin), &[Script::Greek]).is_empty());
+        assert!(check("tθ", Some(Script::Latin), &[Script::Greek]).is_empty());
+        assert_eq!(
+            check("д", Some(Script::Latin), &[Script::Greek]),
+            vec![Issue::MixedScripts(vec![Script::Cyrillic])]
+        );
+    }
+    #[test]
+    fn sections() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("a".to_string(), "α".to_string());
+        map.add("b".to_string(), "β".to_string());
+        map.add("c".to_string(), "c".to_string());
+        kbd.write_section("letters".to_string(), map);
+        let report = audit(&kbd, &[]);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].mappings, 3);
+        assert_eq!(report[0].issues.len(), 1);
+        assert_eq!(report[0].issues[0].0, "c");
+    }
+}
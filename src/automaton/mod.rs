@@ -1,6 +1,7 @@
 use std::{
     collections::{btree_map::Entry, BTreeMap},
     fmt::Write,
+    ops::RangeInclusive,
 };
 
 use crate::kbdwriter::{KbdMap, KbdWriter};
@@ -24,41 +25,31 @@ impl Automaton {
         writeln!(o, "let automaton = [")?;
         for (i, (_, state)) in self.states.iter().enumerate() {
             let accepting = if let Some(mat) = &state.accepting {
-                format!("\"{}\"", mat.clone().replace('\"', "\\\""))
+                format!("\"{}\"", quote_escape(mat))
             } else {
                 "null".to_string()
             };
             write!(
                 o,
                 "    {{l: \"{}\", a: {}, ",
-                state.label.replace('\"', "\\\""),
+                quote_escape(&state.label),
                 accepting
             )?;
             // transitions
             write!(o, "t: ")?;
+            // transitions are ordered by start state, so those of a state are a single range
             let mut transitions = self
                 .transition
-                .iter()
-                .filter(|((start, _), _)| *start == state.state_num)
+                .range((state.state_num, char::MIN)..=(state.state_num, char::MAX))
                 .peekable();
             if transitions.peek().is_some() {
                 let mut add_comma = false;
                 write!(o, "new Map([")?;
                 for ((_, char), end) in transitions {
                     if add_comma {
-                        write!(
-                            o,
-                            ",[\"{}\", {}]",
-                            char.to_string().replace('\"', "\\\""),
-                            end
-                        )?;
+                        write!(o, ",[\"{}\", {}]", quote_escape(&char.to_string()), end)?;
                     } else {
-                        write!(
-                            o,
-                            "[\"{}\", {}]",
-                            char.to_string().replace('\"', "\\\""),
-                            end
-                        )?;
+                        write!(o, "[\"{}\", {}]", quote_escape(&char.to_string()), end)?;
                         add_comma = true;
                     }
                 }
@@ -86,12 +77,8 @@ impl Automaton {
             }
         }
 
-        let state = self.states.get(&cur_state).expect("Should be in map");
-        if let Some(mapped) = &state.accepting {
-            return Some(mapped.clone());
-        }
-
-        None
+        // an automaton without any section has no start state
+        self.states.get(&cur_state)?.accepting.clone()
     }
 
     pub fn from_writer(writer: &KbdWriter) -> (Self, Vec<u32>) {
@@ -195,87 +182,274 @@ impl Automaton {
         o: &mut String,
         ranges: Vec<u32>,
         kbdwriter: &KbdWriter,
-        style: AutomatonStyle,
+        style: &AutomatonStyle,
+        collapse_intermediate: bool,
     ) -> std::fmt::Result {
         writeln!(o, "digraph G {{")?;
-        let mut lower_range = 1;
-        for (cluster_num, (str, range_end)) in kbdwriter
-            .sections
-            .iter()
-            .map(|(str, _)| str)
-            .zip(ranges.iter())
+        writeln!(o, "\trankdir={};", style.rankdir)?;
+        writeln!(o, "\tbgcolor=\"{}\";", style.bgcolor)?;
+        writeln!(
+            o,
+            "\tedge [color=\"{}\",fontcolor=\"{}\"];",
+            style.edge_color, style.edge_color
+        )?;
+        for (cluster_num, (str, range)) in Automaton::section_ranges(&ranges, kbdwriter)
+            .into_iter()
             .enumerate()
         {
             // cluster header
             writeln!(o, "\tsubgraph cluster_{} {{", cluster_num)?;
-            writeln!(o, "\t\tstyle={};", style.cluster_style)?;
-            writeln!(o, "\t\tbgcolor={};", style.cluster_bgcolor)?;
+            writeln!(o, "\t\tstyle=\"{}\";", style.cluster_style)?;
+            writeln!(o, "\t\tbgcolor=\"{}\";", style.cluster_bgcolor)?;
+            writeln!(o, "\t\tfontcolor=\"{}\";", style.cluster_fontcolor)?;
             writeln!(
                 o,
-                "\t\tnode [style=filled,shape={},fillcolor={},fontcolor={}];",
+                "\t\tnode [style=filled,shape=\"{}\",fillcolor=\"{}\",fontcolor=\"{}\"];",
                 style.cluster_node_shape, style.cluster_node_bgcolor, style.cluster_node_fontcolor,
             )?;
-            writeln!(o, "\t\tlabel=\"{}\";", str)?;
+            writeln!(o, "\t\tlabel=\"{}\";", quote_escape(str))?;
 
             // cluster nodes
-            for cur in lower_range..=*range_end {
+            for cur in range {
                 let state = self.states.get(&cur).expect("Map should have value!");
                 if let Some(accepting_str) = &state.accepting {
                     writeln!(
                         o,
                         "\t\t{:3} [label=\"{}\"];",
                         cur,
-                        accepting_str.replace('\"', "\\\"")
+                        quote_escape(accepting_str)
                     )?;
                 }
             }
-            lower_range = range_end + 1;
             writeln!(o, "\t}}")?;
         }
         // transitions
         writeln!(
             o,
-            "\tnode [style=filled,fillcolor={},fontcolor={},shape={}];",
+            "\tnode [style=filled,fillcolor=\"{}\",fontcolor=\"{}\",shape=\"{}\"];",
             style.intermediate_node_bgcolor, style.intermediate_fontcolor, style.intermediate_shape
         )?;
         for (cur, state) in self.states.iter() {
             if *cur == 0 {
                 writeln!(
                     o,
-                    "\t{:3} [style=filled,fillcolor={},fontcolor={},shape={},label=\"{}\"]",
+                    "\t{:3} [style=filled,fillcolor=\"{}\",fontcolor=\"{}\",shape=\"{}\",label=\"{}\"]",
                     cur,
                     style.start_bgcolor,
                     style.start_fontcolor,
                     style.start_shape,
-                    state.label.replace('\"', "\\\"")
+                    quote_escape(&state.label)
                 )?;
             } else if state.accepting.is_none() {
+                if collapse_intermediate {
+                    continue;
+                }
                 writeln!(
                     o,
                     "\t{:3}     \t[label=\"{}\"];",
                     cur,
-                    state.label.replace('\"', "\\\"")
+                    quote_escape(&state.label)
                 )?;
             }
+            if collapse_intermediate {
+                for (next, edge) in self.collapsed_edges(*cur) {
+                    writeln!(
+                        o,
+                        "\t{:3} -> {:3}\t[label=\"{}\"];",
+                        cur,
+                        next,
+                        quote_escape(&edge.replace(' ', "␣"))
+                    )?;
+                }
+                continue;
+            }
             for (next, edge) in state.next.iter() {
-                if edge == &'\"' {
-                    writeln!(o, "\t{:3} -> {:3}\t[label=\"\\\"\"];", cur, next)?;
-                } else if edge == &' ' {
+                if edge == &' ' {
                     writeln!(o, "\t{:3} -> {:3}\t[label=\"space\"];", cur, next)?;
                 } else {
-                    writeln!(o, "\t{:3} -> {:3}\t[label=\"{}\"];", cur, next, edge)?;
+                    writeln!(
+                        o,
+                        "\t{:3} -> {:3}\t[label=\"{}\"];",
+                        cur,
+                        next,
+                        quote_escape(&edge.to_string())
+                    )?;
                 }
             }
         }
         writeln!(o, "}}")?;
         Ok(())
     }
+
+    /// Pairs each section name with the states numbered while joining that section
+    pub fn section_ranges<'a>(
+        ranges: &[u32],
+        kbdwriter: &'a KbdWriter,
+    ) -> Vec<(&'a String, RangeInclusive<u32>)> {
+        let mut lower_range = 1;
+        let mut sections = Vec::new();
+        for ((str, _), range_end) in kbdwriter.sections.iter().zip(ranges.iter()) {
+            sections.push((str, lower_range..=*range_end));
+            lower_range = range_end + 1;
+        }
+        sections
+    }
+
+    pub fn write_mermaid(
+        &self,
+        o: &mut String,
+        ranges: Vec<u32>,
+        kbdwriter: &KbdWriter,
+    ) -> std::fmt::Result {
+        writeln!(o, "stateDiagram-v2")?;
+        let start = self
+            .states
+            .get(&self.start_state)
+            .expect("Should be in map");
+        writeln!(
+            o,
+            "    state \"{}\" as s{}",
+            mermaid_escape(&start.label),
+            self.start_state
+        )?;
+        writeln!(o, "    [*] --> s{}", self.start_state)?;
+        // the composite state each accepting state is nested in
+        let sections = Automaton::section_ranges(&ranges, kbdwriter);
+        let mut clusters: BTreeMap<u32, usize> = BTreeMap::new();
+        for (cluster_num, (_, range)) in sections.iter().enumerate() {
+            for cur in range.clone() {
+                let state = self.states.get(&cur).expect("Map should have value!");
+                if state.accepting.is_some() {
+                    clusters.insert(cur, cluster_num);
+                }
+            }
+        }
+        // transitions between states of one composite state are drawn inside it, the others
+        // at the top level between the same states
+        let mut inner: Vec<Vec<(u32, u32, String)>> = vec![Vec::new(); sections.len()];
+        let mut outer: BTreeMap<(u32, u32), String> = BTreeMap::new();
+        for (cur, state) in self.states.iter() {
+            for (next, edge) in state.next.iter() {
+                let label = match edge {
+                    ' ' => "space".to_string(),
+                    _ => mermaid_escape(&edge.to_string()),
+                };
+                match (clusters.get(cur), clusters.get(next)) {
+                    (Some(from), Some(to)) if from == to => inner[*from].push((*cur, *next, label)),
+                    _ => {
+                        outer.insert((*cur, *next), label);
+                    }
+                }
+            }
+        }
+        // sections as composite states
+        for (cluster_num, (str, range)) in sections.into_iter().enumerate() {
+            writeln!(
+                o,
+                "    state \"{}\" as cluster_{} {{",
+                mermaid_escape(str),
+                cluster_num
+            )?;
+            for cur in range {
+                let state = self.states.get(&cur).expect("Map should have value!");
+                if let Some(accepting_str) = &state.accepting {
+                    writeln!(
+                        o,
+                        "        state \"{}\" as s{}",
+                        mermaid_escape(accepting_str),
+                        cur
+                    )?;
+                }
+            }
+            for (cur, next, label) in inner[cluster_num].iter() {
+                writeln!(o, "        s{} --> s{} : {}", cur, next, label)?;
+            }
+            writeln!(o, "    }}")?;
+        }
+        // intermediate states
+        for (cur, state) in self.states.iter() {
+            if *cur != self.start_state && state.accepting.is_none() {
+                writeln!(
+                    o,
+                    "    state \"{}\" as s{}",
+                    mermaid_escape(&state.label),
+                    cur
+                )?;
+            }
+        }
+        // transitions
+        for ((cur, next), label) in outer.iter() {
+            writeln!(o, "    s{} --> s{} : {}", cur, next, label)?;
+        }
+        Ok(())
+    }
+
+    /// Follows transitions through intermediate states until the next accepting states,
+    /// returning each accepting state with the letters typed to reach it
+    pub fn collapsed_edges(&self, from: u32) -> Vec<(u32, String)> {
+        let mut edges = Vec::new();
+        let mut stack: Vec<(u32, String)> = vec![(from, String::new())];
+        while let Some((cur, typed)) = stack.pop() {
+            let state = self.states.get(&cur).expect("Should be in map");
+            for (next, edge) in state.next.iter().rev() {
+                let mut next_typed = typed.clone();
+                next_typed.push(*edge);
+                let next_state = self.states.get(next).expect("Should be in map");
+                if next_state.accepting.is_some() {
+                    edges.push((*next, next_typed));
+                } else {
+                    stack.push((*next, next_typed));
+                }
+            }
+        }
+        edges.sort();
+        edges
+    }
+}
+
+/// Replaces ascii punctuation with mermaid entity codes
+fn mermaid_escape(str: &str) -> String {
+    let mut escaped = String::new();
+    for c in str.chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push_str(&format!("#{};", c as u32));
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Escapes a string for use inside a double-quoted graphviz label or javascript string
+fn quote_escape(str: &str) -> String {
+    str.replace('\\', "\\\\").replace('\"', "\\\"")
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum StylePreset {
+    #[default]
+    Light,
+    Dark,
+    Monochrome,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RankDir {
+    #[default]
+    TB,
+    LR,
+    BT,
+    RL,
 }
 
 #[derive(Debug)]
 pub struct AutomatonStyle {
+    rankdir: String,
+    bgcolor: String,
+    edge_color: String,
     cluster_style: String,
     cluster_bgcolor: String,
+    cluster_fontcolor: String,
     cluster_node_shape: String,
     cluster_node_bgcolor: String,
     cluster_node_fontcolor: String,
@@ -287,11 +461,85 @@ pub struct AutomatonStyle {
     start_shape: String,
 }
 
+impl AutomatonStyle {
+    pub fn from_preset(preset: StylePreset) -> Self {
+        match preset {
+            StylePreset::Light => Self::default(),
+            StylePreset::Dark => Self {
+                bgcolor: "gray12".to_string(),
+                edge_color: "gray85".to_string(),
+                cluster_bgcolor: "gray22".to_string(),
+                cluster_fontcolor: "white".to_string(),
+                cluster_node_bgcolor: "gray12".to_string(),
+                cluster_node_fontcolor: "white".to_string(),
+                intermediate_node_bgcolor: "gray85".to_string(),
+                intermediate_fontcolor: "black".to_string(),
+                start_bgcolor: "gray12".to_string(),
+                start_fontcolor: "white".to_string(),
+                ..Self::default()
+            },
+            StylePreset::Monochrome => Self {
+                cluster_style: "dashed".to_string(),
+                cluster_bgcolor: "white".to_string(),
+                intermediate_node_bgcolor: "gray85".to_string(),
+                intermediate_fontcolor: "black".to_string(),
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn set_rankdir(&mut self, rankdir: RankDir) {
+        self.rankdir = format!("{:?}", rankdir);
+    }
+
+    /// Overrides one field, given as `field=value`
+    pub fn set(&mut self, setting: &str) -> Result<(), String> {
+        let (field, value) = setting
+            .split_once('=')
+            .ok_or(format!("Style setting '{}' is not 'field=value'", setting))?;
+        let value = value.trim().to_string();
+        match field.trim() {
+            "rankdir" => self.rankdir = value,
+            "bgcolor" => self.bgcolor = value,
+            "edge_color" => self.edge_color = value,
+            "cluster_style" => self.cluster_style = value,
+            "cluster_bgcolor" => self.cluster_bgcolor = value,
+            "cluster_fontcolor" => self.cluster_fontcolor = value,
+            "cluster_node_shape" => self.cluster_node_shape = value,
+            "cluster_node_bgcolor" => self.cluster_node_bgcolor = value,
+            "cluster_node_fontcolor" => self.cluster_node_fontcolor = value,
+            "intermediate_shape" => self.intermediate_shape = value,
+            "intermediate_node_bgcolor" => self.intermediate_node_bgcolor = value,
+            "intermediate_fontcolor" => self.intermediate_fontcolor = value,
+            "start_bgcolor" => self.start_bgcolor = value,
+            "start_fontcolor" => self.start_fontcolor = value,
+            "start_shape" => self.start_shape = value,
+            other => return Err(format!("Unknown style field '{}'", other)),
+        }
+        Ok(())
+    }
+
+    /// Overrides fields from a style file with one `field = value` per line and `#` comments
+    pub fn set_from_file(&mut self, contents: &str) -> Result<(), String> {
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if !line.is_empty() {
+                self.set(line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Default for AutomatonStyle {
     fn default() -> Self {
         Self {
+            rankdir: "TB".to_string(),
+            bgcolor: "white".to_string(),
+            edge_color: "black".to_string(),
             cluster_style: "filled".to_string(),
             cluster_bgcolor: "aliceblue".to_string(),
+            cluster_fontcolor: "black".to_string(),
             cluster_node_shape: "circle".to_string(),
             cluster_node_bgcolor: "white".to_string(),
             cluster_node_fontcolor: "black".to_string(),
@@ -304,3 +552,66 @@ impl Default for AutomatonStyle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn automaton(mappings: &[(&str, &str)]) -> Automaton {
+        let mut map = KbdMap::new();
+        for (seq, mapped) in mappings {
+            map.add(seq.to_string(), mapped.to_string());
+        }
+        Automaton::from_section(&map).0
+    }
+
+    #[test]
+    fn style_settings() {
+        let mut style = AutomatonStyle::from_preset(StylePreset::Dark);
+        assert!(style.set("start_shape = box").is_ok());
+        assert_eq!(style.start_shape, "box");
+        assert!(style.set("nonsense=1").is_err());
+        assert!(style.set("no equals sign").is_err());
+        assert!(style
+            .set_from_file("# comment\nrankdir = LR\n\nbgcolor=black # trailing\n")
+            .is_ok());
+        assert_eq!(style.rankdir, "LR");
+        assert_eq!(style.bgcolor, "black");
+    }
+    #[test]
+    fn mermaid() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("q:".to_string(), "«".to_string());
+        kbd.write_section("punctuation".to_string(), map);
+        let (a, ranges) = Automaton::from_writer(&kbd);
+        let mut output = String::new();
+        a.write_mermaid(&mut output, ranges, &kbd).unwrap();
+        assert_eq!(
+            output,
+            "stateDiagram-v2\n    state \"start\" as s0\n    [*] --> s0\n    \
+            state \"punctuation\" as cluster_0 {\n        state \"«\" as s2\n    }\n    \
+            state \"q\" as s1\n    s0 --> s1 : q\n    s1 --> s2 : #58;\n"
+        );
+        // a transition between states of one section stays inside its composite state
+        let mut map = KbdMap::new();
+        map.add("a".to_string(), "α".to_string());
+        map.add("ab".to_string(), "β".to_string());
+        kbd.write_section("letters".to_string(), map);
+        let (a, ranges) = Automaton::from_writer(&kbd);
+        let mut output = String::new();
+        a.write_mermaid(&mut output, ranges, &kbd).unwrap();
+        assert!(output.contains("        s3 --> s4 : b\n    }"));
+        assert!(output.contains("    s0 --> s3 : a\n"));
+    }
+    #[test]
+    fn collapsed_edges() {
+        let a = automaton(&[("a", "α"), ("aw;", "ᾱ́"), ("q.", "·")]);
+        let edges = a.collapsed_edges(0);
+        let labels: Vec<&str> = edges.iter().map(|(_, str)| str.as_str()).collect();
+        assert_eq!(labels, vec!["a", "q."]);
+        let from_a = a.collapsed_edges(edges[0].0);
+        assert_eq!(from_a.len(), 1);
+        assert_eq!(from_a[0].1, "w;");
+    }
+}
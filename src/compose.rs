@@ -1,53 +1,350 @@
-use std::collections::HashMap;
+use std::fmt;
 
-pub struct CompositionMap {
-    above: HashMap<&'static str, char>,
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use unicode_normalization::UnicodeNormalization;
+use variant_count::VariantCount;
+
+/// Where a diacritic is drawn relative to its base letter
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Position {
+    Above,
+    Below,
+    Through,
+    /// Spanning this letter and the next
+    Double,
+    /// Inside the letter, like the Hebrew dagesh
+    Inside,
+    /// A spacing modifier letter after the letter, like the IPA aspiration
+    Side,
+}
+
+/// A combining diacritic with its code point, position and human name. Layouts name a
+/// diacritic by its variant, so an unknown diacritic is a compile error rather than a failed
+/// lookup by name, and `name` is only written to the output
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    IntoPrimitive,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    TryFromPrimitive,
+    VariantCount,
+)]
+#[repr(u8)]
+pub enum Diacritic {
+    // above
+    Grave,
+    Acute,
+    Circumflex,
+    Tilde,
+    Macron,
+    Overline,
+    Breve,
+    DotAbove,
+    Diaresis,
+    HookAbove,
+    RingAbove,
+    DoubleAcute,
+    Caron,
+    DoubleGrave,
+    InvertedBreve,
+    TurnedComma,
+    Psili,
+    Dasia,
+    Horn,
+    Perispomeni,
+    LeftAngleAbove,
+    XAbove,
+    // below
+    DotBelow,
+    DiaresisBelow,
+    RingBelow,
+    CommaBelow,
+    Cedilla,
+    Ogonek,
+    VerticalLineBelow,
+    BridgeBelow,
+    CaronBelow,
+    CircumflexBelow,
+    BreveBelow,
+    TildeBelow,
+    MacronBelow,
+    LowLine,
+    LeftTackBelow,
+    RightTackBelow,
+    LeftHalfRingBelow,
+    UpTackBelow,
+    DownTackBelow,
+    PlusSignBelow,
+    MinusSignBelow,
+    InvertedBreveBelow,
+    RightHalfRingBelow,
+    InvertedBridgeBelow,
+    SquareBelow,
+    SeagullBelow,
+    Ypogegrammeni,
+    // through
+    TildeOverlay,
+    ShortStroke,
+    LongStroke,
+    ShortSolidus,
+    LongSolidus,
+    // double
+    DoubleBreveBelow,
+    DoubleBreve,
+    DoubleMacron,
+    DoubleMacronBelow,
+    DoubleTilde,
+    DoubleInvertedBreve,
+    // side
+    ModifierH,
+    ModifierW,
+    ModifierJ,
+    ModifierGamma,
+    ModifierReversedGlottalStop,
+    SuperscriptN,
+    ModifierL,
+    ModifierApostrophe,
+    RhoticHook,
+    TriangularColon,
+    HalfTriangularColon,
+    // arabic harakat
+    Fathatan,
+    Dammatan,
+    Kasratan,
+    Fatha,
+    Damma,
+    Kasra,
+    Shadda,
+    Sukun,
+    MaddahAbove,
+    HamzaAbove,
+    HamzaBelow,
+    SuperscriptAlef,
+    // hebrew points
+    Sheva,
+    HatafSegol,
+    HatafPatah,
+    HatafQamats,
+    Hiriq,
+    Tsere,
+    Segol,
+    Patah,
+    Qamats,
+    Holam,
+    Qubuts,
+    Dagesh,
+    Rafe,
+    ShinDot,
+    SinDot,
+    QamatsQatan,
+    // cyrillic marks
+    Titlo,
+    Palatalization,
+    DasiaPneumata,
+    PsiliPneumata,
+    Pokrytie,
+}
+
+impl Diacritic {
+    fn info(self) -> (u32, Position, &'static str) {
+        use Diacritic::*;
+        use Position::*;
+        match self {
+            Grave => (0x300, Above, "grave"),
+            Acute => (0x301, Above, "acute"),
+            Circumflex => (0x302, Above, "circumflex"),
+            Tilde => (0x303, Above, "tilde"),
+            Macron => (0x304, Above, "macron"),
+            Overline => (0x305, Above, "overline"),
+            Breve => (0x306, Above, "breve"),
+            DotAbove => (0x307, Above, "dot above"),
+            Diaresis => (0x308, Above, "diaeresis"),
+            HookAbove => (0x309, Above, "hook above"),
+            RingAbove => (0x30a, Above, "ring above"),
+            DoubleAcute => (0x30b, Above, "double acute"),
+            Caron => (0x30c, Above, "caron"),
+            DoubleGrave => (0x30f, Above, "double grave"),
+            InvertedBreve => (0x311, Above, "inverted breve"),
+            TurnedComma => (0x312, Above, "turned comma above"),
+            Psili => (0x313, Above, "smooth breathing"),
+            Dasia => (0x314, Above, "rough breathing"),
+            Horn => (0x31b, Above, "horn"),
+            Perispomeni => (0x342, Above, "perispomeni"),
+            LeftAngleAbove => (0x31a, Above, "left angle above"),
+            XAbove => (0x33d, Above, "x above"),
+            DotBelow => (0x323, Below, "dot below"),
+            DiaresisBelow => (0x324, Below, "diaeresis below"),
+            RingBelow => (0x325, Below, "ring below"),
+            CommaBelow => (0x326, Below, "comma below"),
+            Cedilla => (0x327, Below, "cedilla"),
+            Ogonek => (0x328, Below, "ogonek"),
+            VerticalLineBelow => (0x329, Below, "vertical line below"),
+            BridgeBelow => (0x32a, Below, "bridge below"),
+            CaronBelow => (0x32c, Below, "caron below"),
+            CircumflexBelow => (0x32d, Below, "circumflex below"),
+            BreveBelow => (0x32e, Below, "breve below"),
+            TildeBelow => (0x330, Below, "tilde below"),
+            MacronBelow => (0x331, Below, "macron below"),
+            LowLine => (0x332, Below, "low line"),
+            LeftTackBelow => (0x318, Below, "left tack below"),
+            RightTackBelow => (0x319, Below, "right tack below"),
+            LeftHalfRingBelow => (0x31c, Below, "left half ring below"),
+            UpTackBelow => (0x31d, Below, "up tack below"),
+            DownTackBelow => (0x31e, Below, "down tack below"),
+            PlusSignBelow => (0x31f, Below, "plus sign below"),
+            MinusSignBelow => (0x320, Below, "minus sign below"),
+            InvertedBreveBelow => (0x32f, Below, "inverted breve below"),
+            RightHalfRingBelow => (0x339, Below, "right half ring below"),
+            InvertedBridgeBelow => (0x33a, Below, "inverted bridge below"),
+            SquareBelow => (0x33b, Below, "square below"),
+            SeagullBelow => (0x33c, Below, "seagull below"),
+            Ypogegrammeni => (0x345, Below, "iota subscript"),
+            TildeOverlay => (0x334, Through, "tilde overlay"),
+            ShortStroke => (0x335, Through, "short stroke"),
+            LongStroke => (0x336, Through, "long stroke"),
+            ShortSolidus => (0x337, Through, "short solidus"),
+            LongSolidus => (0x338, Through, "long solidus"),
+            DoubleBreveBelow => (0x35c, Double, "double breve below"),
+            DoubleBreve => (0x35d, Double, "double breve"),
+            DoubleMacron => (0x35e, Double, "double macron"),
+            DoubleMacronBelow => (0x35f, Double, "double macron below"),
+            DoubleTilde => (0x360, Double, "double tilde"),
+            DoubleInvertedBreve => (0x361, Double, "double inverted breve"),
+            ModifierH => (0x2b0, Side, "aspirated"),
+            ModifierW => (0x2b7, Side, "labialized"),
+            ModifierJ => (0x2b2, Side, "palatalized"),
+            ModifierGamma => (0x2e0, Side, "velarized"),
+            ModifierReversedGlottalStop => (0x2e4, Side, "pharyngealized"),
+            SuperscriptN => (0x207f, Side, "nasal release"),
+            ModifierL => (0x2e1, Side, "lateral release"),
+            ModifierApostrophe => (0x2bc, Side, "ejective"),
+            RhoticHook => (0x2de, Side, "rhoticity"),
+            TriangularColon => (0x2d0, Side, "long"),
+            HalfTriangularColon => (0x2d1, Side, "half-long"),
+            Fathatan => (0x64b, Above, "fathatan"),
+            Dammatan => (0x64c, Above, "dammatan"),
+            Kasratan => (0x64d, Below, "kasratan"),
+            Fatha => (0x64e, Above, "fatha"),
+            Damma => (0x64f, Above, "damma"),
+            Kasra => (0x650, Below, "kasra"),
+            Shadda => (0x651, Above, "shadda"),
+            Sukun => (0x652, Above, "sukun"),
+            MaddahAbove => (0x653, Above, "maddah"),
+            HamzaAbove => (0x654, Above, "hamza above"),
+            HamzaBelow => (0x655, Below, "hamza below"),
+            SuperscriptAlef => (0x670, Above, "superscript alef"),
+            Sheva => (0x5b0, Below, "sheva"),
+            HatafSegol => (0x5b1, Below, "hataf segol"),
+            HatafPatah => (0x5b2, Below, "hataf patah"),
+            HatafQamats => (0x5b3, Below, "hataf qamats"),
+            Hiriq => (0x5b4, Below, "hiriq"),
+            Tsere => (0x5b5, Below, "tsere"),
+            Segol => (0x5b6, Below, "segol"),
+            Patah => (0x5b7, Below, "patah"),
+            Qamats => (0x5b8, Below, "qamats"),
+            Holam => (0x5b9, Above, "holam"),
+            Qubuts => (0x5bb, Below, "qubuts"),
+            Dagesh => (0x5bc, Inside, "dagesh"),
+            Rafe => (0x5bf, Above, "rafe"),
+            ShinDot => (0x5c1, Above, "shin dot"),
+            SinDot => (0x5c2, Above, "sin dot"),
+            QamatsQatan => (0x5c7, Below, "qamats qatan"),
+            Titlo => (0x483, Above, "titlo"),
+            Palatalization => (0x484, Above, "palatalization"),
+            DasiaPneumata => (0x485, Above, "dasia pneumata"),
+            PsiliPneumata => (0x486, Above, "psili pneumata"),
+            Pokrytie => (0x487, Above, "pokrytie"),
+        }
+    }
+
+    pub fn all() -> impl Iterator<Item = Diacritic> {
+        (0..Diacritic::VARIANT_COUNT as u8).map(|d| Diacritic::try_from(d).unwrap())
+    }
+
+    /// The combining character, or the modifier letter of a side mark
+    pub fn char(self) -> char {
+        char::from_u32(self.info().0).unwrap()
+    }
+
+    pub fn combining_class(self) -> u8 {
+        unicode_normalization::char::canonical_combining_class(self.char())
+    }
+
+    pub fn position(self) -> Position {
+        self.info().1
+    }
+
+    /// Human-readable name, for output only
+    pub fn name(self) -> &'static str {
+        self.info().2
+    }
+
+    pub fn from_char(c: char) -> Option<Diacritic> {
+        Diacritic::all().find(|d| d.char() == c)
+    }
+}
+
+/// The diacritics in a string, in canonical order for each letter
+pub fn diacritics(str: &str) -> Vec<Diacritic> {
+    str.nfd().filter_map(Diacritic::from_char).collect()
 }
 
-impl CompositionMap {
-    pub fn new() -> Self {
-        let above: HashMap<&str, char> = HashMap::from([
-            ("grave", char::from_u32(0x0300).unwrap()),
-            ("acute", char::from_u32(0x0301).unwrap()),
-            ("circumflex", char::from_u32(0x302).unwrap()),
-            ("tilde", char::from_u32(0x303).unwrap()),
-            ("macron", char::from_u32(0x304).unwrap()),
-            ("overline", char::from_u32(0x305).unwrap()),
-            ("breve", char::from_u32(0x306).unwrap()),
-            ("dot above", char::from_u32(0x307).unwrap()),
-            ("diaresis", char::from_u32(0x308).unwrap()),
-            ("turned comma", char::from_u32(0x312).unwrap()),
-            ("comma", char::from_u32(0x313).unwrap()),
-            ("reversed comma", char::from_u32(0x314).unwrap()),
-            ("periposmeni", char::from_u32(0x342).unwrap()),
-            ("iota", char::from_u32(0x345).unwrap()),
-        ]);
-        Self { above }
-    }
-
-    pub fn above(&self, name: &str) -> char {
-        *self.above.get(name).unwrap()
+/// Combining marks left over after composing a sequence
+#[derive(Debug, PartialEq)]
+pub struct LooseMarks(pub Vec<char>);
+
+impl fmt::Display for LooseMarks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let marks: Vec<String> = self
+            .0
+            .iter()
+            .map(|c| match Diacritic::from_char(*c) {
+                Some(diacritic) => format!("U+{:04X} {}", *c as u32, diacritic.name()),
+                None => format!("U+{:04X}", *c as u32),
+            })
+            .collect();
+        write!(f, "Loose combining marks: {}", marks.join(", "))
     }
 }
 
+impl std::error::Error for LooseMarks {}
+
+/// Composes a sequence to NFC: marks are sorted by canonical combining class,
+/// then each is composed with its letter wherever a precomposed character exists
 pub fn compose_vec(seq: Vec<char>) -> Vec<char> {
-    if seq.is_empty() {
-        return seq;
-    }
-    seq[1..]
+    seq.into_iter().nfc().collect()
+}
+
+/// Whether a mark is one of the combining diacritics shared by every script,
+/// rather than a mark of one script, like a vowel sign, that is never precomposed
+fn is_generic_mark(c: char) -> bool {
+    matches!(c,
+        '\u{300}'..='\u{36f}'
+        | '\u{1ab0}'..='\u{1aff}'
+        | '\u{1dc0}'..='\u{1dff}'
+        | '\u{20d0}'..='\u{20ff}'
+        | '\u{fe20}'..='\u{fe2f}')
+}
+
+/// Like `compose_vec`, but fails if any combining diacritic is left uncomposed.
+/// Marks before the first letter are typed on their own, to go on the letter typed before them
+pub fn compose_strict(seq: Vec<char>) -> Result<Vec<char>, LooseMarks> {
+    let composed = compose_vec(seq);
+    let loose: Vec<char> = composed
         .iter()
-        .fold(vec![seq[0]], |mut acc, next| -> Vec<char> {
-            match unicode_normalization::char::compose(*acc.last().unwrap(), *next) {
-                Some(out) => {
-                    acc.pop();
-                    acc.push(out);
-                }
-                None => {
-                    acc.push(*next);
-                }
-            }
-            acc
-        })
+        .copied()
+        .skip_while(|c| unicode_normalization::char::is_combining_mark(*c))
+        .filter(|c| is_generic_mark(*c))
+        .collect();
+    match loose.is_empty() {
+        true => Ok(composed),
+        false => Err(LooseMarks(loose)),
+    }
 }
 
 #[cfg(test)]
@@ -103,7 +400,72 @@ mod tests {
         assert_eq!(compose_vec(vec!['α', '\u{314}']), vec!['ἁ'])
     }
     #[test]
+    fn below() {
+        assert_eq!(compose_vec(vec!['s', Diacritic::Cedilla.char()]), vec!['ş']);
+        assert_eq!(compose_vec(vec!['a', Diacritic::Ogonek.char()]), vec!['ą']);
+        assert_eq!(compose_vec(vec!['c', Diacritic::Caron.char()]), vec!['č']);
+    }
+    #[test]
+    fn diacritic_info() {
+        assert_eq!(Diacritic::LongStroke.char(), '\u{336}');
+        assert_eq!(Diacritic::DoubleBreve.position(), Position::Double);
+        assert_eq!(Diacritic::Psili.combining_class(), 230);
+        assert_eq!(Diacritic::Ypogegrammeni.combining_class(), 240);
+        assert_eq!(Diacritic::Ypogegrammeni.position(), Position::Below);
+        assert_eq!(Diacritic::DotBelow.combining_class(), 220);
+        assert_eq!(
+            Diacritic::from_char('\u{342}'),
+            Some(Diacritic::Perispomeni)
+        );
+        assert_eq!(Diacritic::from_char('a'), None);
+        assert_eq!(Diacritic::all().count(), Diacritic::VARIANT_COUNT);
+    }
+    #[test]
+    fn diacritic_names() {
+        let names: Vec<&str> = diacritics("ᾄ").iter().map(|d| d.name()).collect();
+        assert_eq!(names, vec!["smooth breathing", "acute", "iota subscript"]);
+        assert!(diacritics("β").is_empty());
+    }
+    #[test]
+    fn canonical_order() {
+        // iota subscript sorts after the acute, which composes first
+        assert_eq!(compose_vec(vec!['α', '\u{345}', '\u{301}']), vec!['ᾴ']);
+        assert_eq!(
+            compose_vec(vec!['α', '\u{345}', '\u{313}', '\u{342}']),
+            vec!['ᾆ']
+        );
+        assert_eq!(
+            compose_vec(vec!['a', '\u{323}', '\u{302}']),
+            compose_vec(vec!['a', '\u{302}', '\u{323}'])
+        );
+    }
+    #[test]
+    fn vietnamese() {
+        assert_eq!(compose_vec(vec!['a', '\u{302}', '\u{323}']), vec!['ậ']);
+        assert_eq!(compose_vec(vec!['u', '\u{31b}', '\u{323}']), vec!['ự']);
+        assert_eq!(compose_vec(vec!['O', '\u{31b}', '\u{303}']), vec!['Ỡ']);
+    }
+    #[test]
+    fn strict() {
+        assert_eq!(compose_strict(vec!['α', '\u{314}']), Ok(vec!['ἁ']));
+        assert_eq!(
+            compose_strict(vec!['υ', '\u{304}', '\u{308}']),
+            Err(LooseMarks(vec!['\u{308}']))
+        );
+        assert_eq!(compose_strict(vec!['क', 'ि']), Ok(vec!['क', 'ि']));
+        assert_eq!(compose_strict(vec!['\u{325}']), Ok(vec!['\u{325}']));
+        assert_eq!(
+            compose_strict(vec!['ب', Diacritic::Fatha.char()]),
+            Ok(vec!['ب', '\u{64e}'])
+        );
+        assert_eq!(
+            LooseMarks(vec!['\u{308}']).to_string(),
+            "Loose combining marks: U+0308 diaeresis"
+        );
+    }
+    #[test]
     fn diaresis() {
+        // there is no precomposed upsilon with macron and diaeresis
         assert_eq!(
             compose_vec(vec!['υ', '\u{304}', '\u{308}']),
             vec!['ῡ', '\u{308}']
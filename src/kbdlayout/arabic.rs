@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use crate::{
+    compose::{compose_vec, Diacritic},
+    kbdwriter::{KbdMap, KbdMetaData, KbdWriter},
+};
+
+/// The letter carrying a hamza between two vowels: kasra takes a yeh, damma a waw,
+/// anything else an alif. A word-initial hamza sits on an alif, below it before a kasra
+fn hamza_carrier(before: Option<Diacritic>, after: Option<Diacritic>) -> char {
+    let vowels = [before, after];
+    let (letter, hamza) = if before.is_none() {
+        match after {
+            Some(Diacritic::Kasra) => ('ا', Diacritic::HamzaBelow),
+            _ => ('ا', Diacritic::HamzaAbove),
+        }
+    } else if vowels.contains(&Some(Diacritic::Kasra)) {
+        ('ي', Diacritic::HamzaAbove)
+    } else if vowels.contains(&Some(Diacritic::Damma)) {
+        ('و', Diacritic::HamzaAbove)
+    } else {
+        ('ا', Diacritic::HamzaAbove)
+    };
+    compose_vec(vec![letter, hamza.char()])[0]
+}
+
+pub fn gen(keyboard: &mut KbdWriter) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Arabic".to_string(),
+        "ar".to_string(),
+        "Arabic phonetic layout with harakat".to_string(),
+    ));
+
+    // modifier keys
+    let modifier_hamza: char = '\'';
+    keyboard.add_modifier(modifier_hamza, "hamza".to_string());
+
+    let letters: HashMap<&str, char> = HashMap::from([
+        ("A", 'ا'),
+        ("b", 'ب'),
+        ("t", 'ت'),
+        ("th", 'ث'),
+        ("j", 'ج'),
+        ("H", 'ح'),
+        ("kh", 'خ'),
+        ("d", 'د'),
+        ("dh", 'ذ'),
+        ("r", 'ر'),
+        ("z", 'ز'),
+        ("s", 'س'),
+        ("sh", 'ش'),
+        ("S", 'ص'),
+        ("D", 'ض'),
+        ("T", 'ط'),
+        ("Z", 'ظ'),
+        ("e", 'ع'),
+        ("gh", 'غ'),
+        ("f", 'ف'),
+        ("q", 'ق'),
+        ("k", 'ك'),
+        ("l", 'ل'),
+        ("m", 'م'),
+        ("n", 'ن'),
+        ("h", 'ه'),
+        ("w", 'و'),
+        ("y", 'ي'),
+        ("h.", 'ة'),
+        ("Y", 'ى'),
+    ]);
+
+    let vowel_key: Vec<char> = vec!['a', 'i', 'u'];
+    let vowels: Vec<Diacritic> = vec![Diacritic::Fatha, Diacritic::Kasra, Diacritic::Damma];
+    let tanwin: Vec<Diacritic> = vec![
+        Diacritic::Fathatan,
+        Diacritic::Kasratan,
+        Diacritic::Dammatan,
+    ];
+    let sukun_key: char = 'o';
+    let shadda_key: char = '~';
+    let superscript_alef_key: char = '`';
+
+    let punctuation: Vec<(char, char)> = vec![(',', '،'), (';', '؛'), ('?', '؟'), ('_', 'ـ')];
+    let digits = "٠١٢٣٤٥٦٧٨٩";
+
+    // letters
+    let mut letter_map = KbdMap::new();
+    for (sequence, letter) in letters.iter() {
+        letter_map.add(sequence.to_string(), letter.to_string());
+    }
+    keyboard.write_section("letters".to_string(), letter_map);
+
+    // harakat, typed after the letter they belong to
+    let mut harakat_map = KbdMap::new();
+    for ((key, vowel), nunated) in vowel_key.iter().zip(vowels.iter()).zip(tanwin.iter()) {
+        harakat_map.add(key.to_string(), vowel.char().to_string());
+        harakat_map.add([*key, 'N'].iter().collect(), nunated.char().to_string());
+    }
+    harakat_map.add(sukun_key.to_string(), Diacritic::Sukun.char().to_string());
+    harakat_map.add(shadda_key.to_string(), Diacritic::Shadda.char().to_string());
+    harakat_map.add(
+        superscript_alef_key.to_string(),
+        Diacritic::SuperscriptAlef.char().to_string(),
+    );
+    keyboard.write_section("harakat".to_string(), harakat_map);
+
+    // hamza on each carrier
+    let mut hamza_map = KbdMap::new();
+    hamza_map.add(modifier_hamza.to_string(), 'ء'.to_string());
+    for (carrier, below) in [('A', false), ('w', false), ('y', false), ('I', true)] {
+        let letter = match carrier {
+            'w' => 'و',
+            'y' => 'ي',
+            _ => 'ا',
+        };
+        let hamza = match below {
+            true => Diacritic::HamzaBelow,
+            false => Diacritic::HamzaAbove,
+        };
+        hamza_map.add(
+            [carrier, modifier_hamza].iter().collect(),
+            compose_vec(vec![letter, hamza.char()]).iter().collect(),
+        );
+    }
+    hamza_map.add(
+        [modifier_hamza, 'A'].iter().collect(),
+        compose_vec(vec!['ا', Diacritic::MaddahAbove.char()])
+            .iter()
+            .collect(),
+    );
+    keyboard.write_section("hamza".to_string(), hamza_map);
+
+    // hamza with the carrier picked from the vowels around it
+    let mut contextual_map = KbdMap::new();
+    let before_keys = vowel_key
+        .iter()
+        .zip(vowels.iter())
+        .chain([(&sukun_key, &Diacritic::Sukun)]);
+    for (key, vowel) in vowel_key.iter().zip(vowels.iter()) {
+        // word initial
+        contextual_map.add(
+            [modifier_hamza, *key].iter().collect(),
+            [hamza_carrier(None, Some(*vowel)), vowel.char()]
+                .iter()
+                .collect(),
+        );
+    }
+    for (before_key, before) in before_keys {
+        // closing a syllable
+        if *before != Diacritic::Sukun {
+            contextual_map.add(
+                [*before_key, modifier_hamza].iter().collect(),
+                [before.char(), hamza_carrier(Some(*before), None)]
+                    .iter()
+                    .collect(),
+            );
+        }
+        // between two vowels
+        for (after_key, after) in vowel_key.iter().zip(vowels.iter()) {
+            contextual_map.add(
+                [*before_key, modifier_hamza, *after_key].iter().collect(),
+                [
+                    before.char(),
+                    hamza_carrier(Some(*before), Some(*after)),
+                    after.char(),
+                ]
+                .iter()
+                .collect(),
+            );
+        }
+    }
+    keyboard.write_section("contextual hamza".to_string(), contextual_map);
+
+    // punctuation
+    let mut punctuation_map = KbdMap::new();
+    for (key, mark) in punctuation.iter() {
+        punctuation_map.add(key.to_string(), mark.to_string());
+    }
+    keyboard.write_section("punctuation".to_string(), punctuation_map);
+
+    // digits
+    let mut digit_map = KbdMap::new();
+    for (key, digit) in ('0'..='9').zip(digits.chars()) {
+        digit_map.add(key.to_string(), digit.to_string());
+    }
+    keyboard.write_section("digits".to_string(), digit_map);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carrier() {
+        let (fatha, damma, kasra) = (Diacritic::Fatha, Diacritic::Damma, Diacritic::Kasra);
+        // word initial
+        assert_eq!(hamza_carrier(None, Some(fatha)), '\u{623}');
+        assert_eq!(hamza_carrier(None, Some(damma)), '\u{623}');
+        assert_eq!(hamza_carrier(None, Some(kasra)), '\u{625}');
+        // kasra before damma before fatha
+        assert_eq!(hamza_carrier(Some(damma), Some(kasra)), '\u{626}');
+        assert_eq!(hamza_carrier(Some(kasra), Some(damma)), '\u{626}');
+        assert_eq!(hamza_carrier(Some(fatha), Some(damma)), '\u{624}');
+        assert_eq!(hamza_carrier(Some(fatha), Some(fatha)), '\u{623}');
+        // after a sukun the vowel after the hamza decides
+        assert_eq!(
+            hamza_carrier(Some(Diacritic::Sukun), Some(fatha)),
+            '\u{623}'
+        );
+        assert_eq!(
+            hamza_carrier(Some(Diacritic::Sukun), Some(kasra)),
+            '\u{626}'
+        );
+    }
+
+    #[test]
+    fn contextual_hamza() {
+        let mut kbd = KbdWriter::new();
+        gen(&mut kbd);
+        let (_, contextual) = kbd
+            .sections
+            .iter()
+            .find(|(name, _)| name == "contextual hamza")
+            .unwrap();
+        let mapped = |seq: &str| {
+            contextual
+                .iter()
+                .find(|(s, _)| *s == seq)
+                .map(|(_, mapped)| mapped.clone())
+        };
+        assert_eq!(mapped("'a"), Some("\u{623}\u{64e}".to_string()));
+        assert_eq!(mapped("'i"), Some("\u{625}\u{650}".to_string()));
+        assert_eq!(mapped("i'"), Some("\u{650}\u{626}".to_string()));
+        assert_eq!(mapped("u'"), Some("\u{64f}\u{624}".to_string()));
+        assert_eq!(mapped("u'a"), Some("\u{64f}\u{624}\u{64e}".to_string()));
+        assert_eq!(mapped("a'i"), Some("\u{64e}\u{626}\u{650}".to_string()));
+        assert_eq!(mapped("o'i"), Some("\u{652}\u{626}\u{650}".to_string()));
+        assert_eq!(mapped("o'a"), Some("\u{652}\u{623}\u{64e}".to_string()));
+        // a sukun alone does not close a syllable on a hamza
+        assert_eq!(mapped("o'"), None);
+    }
+}
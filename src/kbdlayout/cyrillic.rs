@@ -0,0 +1,406 @@
+use crate::{
+    compose::{compose_vec, Diacritic},
+    kbdwriter::{KbdMap, KbdMetaData, KbdWriter},
+};
+
+/// Consonants typed the same way in every Cyrillic layout
+const COMMON_CONSONANTS: [(&str, char); 17] = [
+    ("b", 'б'),
+    ("v", 'в'),
+    ("d", 'д'),
+    ("zh", 'ж'),
+    ("z", 'з'),
+    ("k", 'к'),
+    ("l", 'л'),
+    ("m", 'м'),
+    ("n", 'н'),
+    ("p", 'п'),
+    ("r", 'р'),
+    ("s", 'с'),
+    ("t", 'т'),
+    ("f", 'ф'),
+    ("ts", 'ц'),
+    ("ch", 'ч'),
+    ("sh", 'ш'),
+];
+
+/// A phonetic Cyrillic layout: soft vowels are typed after `j`, signs after `q`
+/// and stress after the vowel with `;`
+struct Cyrillic {
+    metadata: KbdMetaData,
+    /// Consonants on top of the common ones
+    consonants: Vec<(&'static str, char)>,
+    /// A vowel key, its hard letter and its soft letter if there is one
+    vowels: Vec<(char, char, Option<char>)>,
+    /// Signs typed after the sign key, they never start a word
+    signs: Vec<(char, char)>,
+    /// Letters out of modern use, typed after the sign key
+    archaic: Vec<(char, char)>,
+    /// Combining marks typed after the letter they belong to
+    marks: Vec<(char, Diacritic)>,
+}
+
+impl Cyrillic {
+    fn gen(self, keyboard: &mut KbdWriter) {
+        keyboard.set_metadata(self.metadata);
+
+        let soft_vowels: Vec<(char, char)> = self
+            .vowels
+            .iter()
+            .filter_map(|(key, _, soft)| soft.map(|soft| (*key, soft)))
+            .collect();
+
+        // modifier keys
+        let modifier_sign: char = 'q';
+        let modifier_soft: char = 'j';
+        let modifier_acute: char = ';';
+        let modifier_mark: char = '`';
+        if !self.signs.is_empty() || !self.archaic.is_empty() {
+            keyboard.add_modifier(
+                modifier_sign,
+                match self.archaic.is_empty() {
+                    true => "sign".to_string(),
+                    false => "sign, archaic letter".to_string(),
+                },
+            );
+        }
+        if !soft_vowels.is_empty() {
+            keyboard.add_modifier(modifier_soft, "soft".to_string());
+        }
+        keyboard.add_modifier(modifier_acute, Diacritic::Acute.name().to_string());
+        if !self.marks.is_empty() {
+            keyboard.add_modifier(modifier_mark, "combining mark".to_string());
+        }
+
+        let mut consonant_map = KbdMap::new();
+        for (sequence, letter) in COMMON_CONSONANTS.iter().chain(self.consonants.iter()) {
+            consonant_map.add(sequence.to_string(), letter.to_string());
+            consonant_map.add(
+                sequence.to_string().to_uppercase(),
+                letter.to_uppercase().to_string(),
+            );
+        }
+        keyboard.write_section("consonants".to_string(), consonant_map);
+
+        let mut hard_vowel_map = KbdMap::new();
+        for (vowel, mapped, _) in self.vowels.iter() {
+            hard_vowel_map.add(vowel.to_string(), mapped.to_string());
+            hard_vowel_map.add(
+                vowel.to_string().to_uppercase(),
+                mapped.to_uppercase().to_string(),
+            );
+        }
+        keyboard.write_section("vowels".to_string(), hard_vowel_map);
+
+        if !soft_vowels.is_empty() {
+            let mut soft_vowel_map = KbdMap::new();
+            for (vowel, mapped) in soft_vowels.iter() {
+                let mut seq = modifier_soft.to_string();
+                seq.push(*vowel);
+                soft_vowel_map.add(seq.clone(), mapped.to_string());
+                seq.pop();
+                seq = seq.to_uppercase();
+                seq.push_str(&vowel.to_uppercase().to_string());
+                soft_vowel_map.add(seq.clone(), mapped.to_uppercase().to_string());
+                seq.pop();
+                seq.push(*vowel);
+                soft_vowel_map.add(seq, mapped.to_uppercase().to_string());
+            }
+            keyboard.write_section("soft vowels".to_string(), soft_vowel_map);
+        }
+
+        let mut acute_hard_vowel_map = KbdMap::new();
+        for (vowel, mapped, _) in self.vowels.iter() {
+            acute_hard_vowel_map.add(
+                [*vowel, modifier_acute].iter().collect(),
+                compose_vec(vec![*mapped, Diacritic::Acute.char()])
+                    .iter()
+                    .collect(),
+            );
+            let in_str: Vec<char> = vowel.to_uppercase().collect();
+            let map_str: Vec<char> = mapped.to_uppercase().collect();
+            acute_hard_vowel_map.add(
+                [in_str[0], modifier_acute].iter().collect(),
+                compose_vec(vec![map_str[0], Diacritic::Acute.char()])
+                    .iter()
+                    .collect(),
+            );
+        }
+        keyboard.write_section("acute hard vowels".to_string(), acute_hard_vowel_map);
+
+        if !soft_vowels.is_empty() {
+            let mut acute_soft_vowel_map = KbdMap::new();
+            for (vowel, mapped) in soft_vowels.iter() {
+                acute_soft_vowel_map.add(
+                    [modifier_soft, *vowel, modifier_acute].iter().collect(),
+                    compose_vec(vec![*mapped, Diacritic::Acute.char()])
+                        .iter()
+                        .collect(),
+                );
+                let mod_str: Vec<char> = modifier_soft.to_uppercase().collect();
+                let in_str: Vec<char> = vowel.to_uppercase().collect();
+                let map_str: Vec<char> = mapped.to_uppercase().collect();
+
+                acute_soft_vowel_map.add(
+                    [mod_str[0], in_str[0], modifier_acute].iter().collect(),
+                    compose_vec(vec![map_str[0], Diacritic::Acute.char()])
+                        .iter()
+                        .collect(),
+                );
+                acute_soft_vowel_map.add(
+                    [mod_str[0], *vowel, modifier_acute].iter().collect(),
+                    compose_vec(vec![map_str[0], Diacritic::Acute.char()])
+                        .iter()
+                        .collect(),
+                );
+            }
+            keyboard.write_section("acute soft vowels".to_string(), acute_soft_vowel_map);
+        }
+
+        if !self.signs.is_empty() {
+            let mut sign_map = KbdMap::new();
+            for (key, sign) in self.signs.iter() {
+                let mut seq = modifier_sign.to_string();
+                seq.push(*key);
+                sign_map.add(seq.clone(), sign.to_string());
+                seq.pop();
+                seq.push_str(&key.to_uppercase().to_string());
+                sign_map.add(seq, sign.to_string());
+            }
+            keyboard.write_section("signs".to_string(), sign_map);
+        }
+
+        if !self.archaic.is_empty() {
+            let mut archaic_map = KbdMap::new();
+            for (key, letter) in self.archaic.iter() {
+                archaic_map.add([modifier_sign, *key].iter().collect(), letter.to_string());
+                archaic_map.add(
+                    [modifier_sign]
+                        .into_iter()
+                        .chain(key.to_uppercase())
+                        .collect(),
+                    letter.to_uppercase().to_string(),
+                );
+            }
+            keyboard.write_section("archaic letters".to_string(), archaic_map);
+        }
+
+        if !self.marks.is_empty() {
+            let mut mark_map = KbdMap::new();
+            for (key, mark) in self.marks.iter() {
+                mark_map.add(
+                    [modifier_mark, *key].iter().collect(),
+                    mark.char().to_string(),
+                );
+            }
+            keyboard.write_section("combining marks".to_string(), mark_map);
+        }
+    }
+}
+
+pub fn gen_russian(keyboard: &mut KbdWriter) {
+    Cyrillic {
+        metadata: KbdMetaData::new(
+            "Russian Cyrillic".to_string(),
+            "rus".to_string(),
+            "Russian phonetic layout".to_string(),
+        ),
+        consonants: vec![("g", 'г'), ("j", 'й'), ("h", 'х'), ("sch", 'щ')],
+        vowels: vec![
+            ('a', 'а', Some('я')),
+            ('e', 'э', Some('е')),
+            ('o', 'о', Some('ё')),
+            ('u', 'у', Some('ю')),
+            ('i', 'и', None),
+            ('y', 'ы', None),
+        ],
+        signs: vec![('s', 'ь'), ('h', 'ъ')],
+        archaic: vec![],
+        marks: vec![],
+    }
+    .gen(keyboard);
+}
+
+pub fn gen_ukrainian(keyboard: &mut KbdWriter) {
+    Cyrillic {
+        metadata: KbdMetaData::new(
+            "Ukrainian".to_string(),
+            "ukr".to_string(),
+            "Ukrainian phonetic layout".to_string(),
+        ),
+        consonants: vec![
+            ("h", 'г'),
+            ("g", 'ґ'),
+            ("j", 'й'),
+            ("kh", 'х'),
+            ("sch", 'щ'),
+        ],
+        vowels: vec![
+            ('a', 'а', Some('я')),
+            ('e', 'е', Some('є')),
+            ('o', 'о', None),
+            ('u', 'у', Some('ю')),
+            ('i', 'і', Some('ї')),
+            ('y', 'и', None),
+        ],
+        // the apostrophe takes the place of the hard sign
+        signs: vec![('s', 'ь'), ('h', 'ʼ')],
+        archaic: vec![],
+        marks: vec![],
+    }
+    .gen(keyboard);
+}
+
+pub fn gen_belarusian(keyboard: &mut KbdWriter) {
+    Cyrillic {
+        metadata: KbdMetaData::new(
+            "Belarusian".to_string(),
+            "bel".to_string(),
+            "Belarusian phonetic layout".to_string(),
+        ),
+        consonants: vec![("h", 'г'), ("j", 'й'), ("kh", 'х'), ("w", 'ў')],
+        vowels: vec![
+            ('a', 'а', Some('я')),
+            ('e', 'э', Some('е')),
+            ('o', 'о', Some('ё')),
+            ('u', 'у', Some('ю')),
+            ('i', 'і', None),
+            ('y', 'ы', None),
+        ],
+        // the apostrophe takes the place of the hard sign
+        signs: vec![('s', 'ь'), ('h', 'ʼ')],
+        archaic: vec![],
+        marks: vec![],
+    }
+    .gen(keyboard);
+}
+
+/// Serbian writes j rather than soft vowels, so j after a consonant softens it
+pub fn gen_serbian(keyboard: &mut KbdWriter) {
+    Cyrillic {
+        metadata: KbdMetaData::new(
+            "Serbian Cyrillic".to_string(),
+            "srp".to_string(),
+            "Serbian phonetic layout".to_string(),
+        ),
+        consonants: vec![
+            ("g", 'г'),
+            ("j", 'ј'),
+            ("h", 'х'),
+            ("dj", 'ђ'),
+            ("lj", 'љ'),
+            ("nj", 'њ'),
+            ("tj", 'ћ'),
+            ("dzh", 'џ'),
+        ],
+        vowels: vec![
+            ('a', 'а', None),
+            ('e', 'е', None),
+            ('o', 'о', None),
+            ('u', 'у', None),
+            ('i', 'и', None),
+        ],
+        signs: vec![],
+        archaic: vec![],
+        marks: vec![],
+    }
+    .gen(keyboard);
+}
+
+pub fn gen_bulgarian(keyboard: &mut KbdWriter) {
+    Cyrillic {
+        metadata: KbdMetaData::new(
+            "Bulgarian".to_string(),
+            "bul".to_string(),
+            "Bulgarian phonetic layout".to_string(),
+        ),
+        consonants: vec![("g", 'г'), ("j", 'й'), ("h", 'х'), ("sht", 'щ')],
+        vowels: vec![
+            ('a', 'а', Some('я')),
+            ('e', 'е', None),
+            ('o', 'о', None),
+            ('u', 'у', Some('ю')),
+            ('i', 'и', None),
+            // the vowel ъ
+            ('y', 'ъ', None),
+        ],
+        signs: vec![('s', 'ь')],
+        archaic: vec![],
+        marks: vec![],
+    }
+    .gen(keyboard);
+}
+
+pub fn gen_church_slavonic(keyboard: &mut KbdWriter) {
+    Cyrillic {
+        metadata: KbdMetaData::new(
+            "Church Slavonic".to_string(),
+            "chu".to_string(),
+            "Church Slavonic phonetic layout with titlo and other combining marks".to_string(),
+        ),
+        consonants: vec![
+            ("g", 'г'),
+            ("j", 'й'),
+            ("h", 'х'),
+            ("sch", 'щ'),
+            ("dz", 'ѕ'),
+            ("ks", 'ѯ'),
+            ("ps", 'ѱ'),
+            ("th", 'ѳ'),
+        ],
+        vowels: vec![
+            ('a', 'а', Some('ꙗ')),
+            ('e', 'є', Some('ѥ')),
+            ('o', 'о', None),
+            ('u', 'ꙋ', Some('ю')),
+            ('i', 'и', None),
+            ('y', 'ы', None),
+        ],
+        signs: vec![('s', 'ь'), ('h', 'ъ')],
+        archaic: vec![
+            ('e', 'ѣ'),
+            ('i', 'і'),
+            ('y', 'ѵ'),
+            ('o', 'ѡ'),
+            ('t', 'ѿ'),
+            ('a', 'ѧ'),
+            ('u', 'ѫ'),
+        ],
+        marks: vec![
+            ('t', Diacritic::Titlo),
+            ('p', Diacritic::Pokrytie),
+            ('j', Diacritic::Palatalization),
+            ('s', Diacritic::PsiliPneumata),
+            ('d', Diacritic::DasiaPneumata),
+            ('g', Diacritic::Grave),
+            ('k', Diacritic::InvertedBreve),
+        ],
+    }
+    .gen(keyboard);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optional_sections() {
+        let mut kbd = KbdWriter::new();
+        gen_serbian(&mut kbd);
+        let sections: Vec<&String> = kbd.sections.iter().map(|(name, _)| name).collect();
+        assert_eq!(sections, vec!["consonants", "vowels", "acute hard vowels"]);
+        assert!(!kbd.modifiers.contains_key(&'q'));
+        assert!(!kbd.modifiers.contains_key(&'j'));
+
+        let mut kbd = KbdWriter::new();
+        gen_church_slavonic(&mut kbd);
+        let (_, archaic) = kbd
+            .sections
+            .iter()
+            .find(|(name, _)| name == "archaic letters")
+            .unwrap();
+        let mapped: Vec<(&String, &String)> = archaic.iter().collect();
+        assert!(mapped.contains(&(&"qE".to_string(), &"Ѣ".to_string())));
+        assert!(mapped.contains(&(&"qe".to_string(), &"ѣ".to_string())));
+    }
+}
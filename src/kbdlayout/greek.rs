@@ -1,21 +1,137 @@
 use std::collections::{HashMap, VecDeque};
 
 use crate::{
-    compose::{compose_vec, CompositionMap},
+    compose::{compose_vec, Diacritic},
     kbdwriter::{KbdMap, KbdMetaData, KbdWriter},
 };
 
+const VOWEL_KEY: &[char] = &['a', 'e', 'i', 'o', 'u'];
+const SHORT_VOWELS: &[char] = &['α', 'ε', 'ι', 'ο', 'υ'];
+const LONG_VOWELS: &[char] = &['ᾱ', 'η', 'ῑ', 'ω', 'ῡ'];
+
+/// Consonants, doubled letters, final forms and punctuation, shared by the polytonic
+/// and monotonic layouts
+fn write_alphabet(keyboard: &mut KbdWriter, modifiers: &[char], modifier_special: char) {
+    let consonants: HashMap<&str, char> = HashMap::from([
+        ("b", 'β'),
+        ("g", 'γ'),
+        ("d", 'δ'),
+        ("z", 'ζ'),
+        ("th", 'θ'),
+        ("k", 'κ'),
+        ("l", 'λ'),
+        ("m", 'μ'),
+        ("n", 'ν'),
+        ("ks", 'ξ'),
+        ("p", 'π'),
+        ("r", 'ρ'),
+        ("s", 'σ'),
+        ("t", 'τ'),
+        ("ph", 'φ'),
+        ("kh", 'χ'),
+        ("ps", 'ψ'),
+    ]);
+    let alphabet_doubles: Vec<(String, char)> = vec![
+        ("h".to_string(), 'η'), // non-phonetic
+        ("c".to_string(), 'κ'),
+        ("f".to_string(), 'φ'),
+        ("ch".to_string(), 'χ'),
+        ("x".to_string(), 'ξ'),
+        ("v".to_string(), 'ω'), // non-phonetic
+    ];
+    let final_consonants: Vec<(char, char)> = vec![('σ', 'ς')];
+
+    let punctuation: Vec<(char, char)> = vec![('.', '·'), ('<', '«'), ('>', '»'), ('?', ';')];
+
+    // basic alphabet
+    let mut alphabet_map = KbdMap::new();
+    for (sequence, letter) in consonants.iter() {
+        // lowercase
+        alphabet_map.add(sequence.to_string(), letter.to_string());
+        // capital
+        let mut cap_seq = sequence.to_string().to_uppercase();
+        let cap_letter = letter.to_uppercase().to_string();
+        if cap_seq.len() > 1 {
+            let last = sequence.chars().nth(1).unwrap();
+            //println!("long: {} -> {}, last char: {}", sequence, letter, last);
+            // if the 2nd character is not a modifier
+            if modifiers.contains(&last) {
+                //println!("  modifiers contains {}", last);
+                cap_seq.pop();
+                cap_seq.push(last);
+                alphabet_map.add(cap_seq, cap_letter);
+            } else {
+                alphabet_map.add(cap_seq, cap_letter);
+            }
+        } else {
+            alphabet_map.add(cap_seq, cap_letter);
+        }
+    }
+    keyboard.write_section("consonants".to_string(), alphabet_map);
+
+    // doubled alphabet
+    let mut alphabet_doubles_map = KbdMap::new();
+    for (sequence, letter) in alphabet_doubles {
+        // lowercase
+        alphabet_doubles_map.add(sequence.clone(), letter.to_string());
+        // capital
+        let mut cap_seq = sequence.to_uppercase();
+        let cap_letter = letter.to_uppercase().to_string();
+        if cap_seq.len() > 1 {
+            let last = sequence.chars().nth(1).unwrap();
+            //println!("long: {} -> {}, last char: {}", sequence, letter, last);
+            // if the 2nd character is not a modifier
+            if modifiers.contains(&last) {
+                //println!("  modifiers contains {}", last);
+                cap_seq.pop();
+                cap_seq.push(last);
+                alphabet_doubles_map.add(cap_seq, cap_letter);
+            } else {
+                alphabet_doubles_map.add(cap_seq, cap_letter);
+            }
+        } else {
+            alphabet_doubles_map.add(cap_seq, cap_letter);
+        }
+    }
+    keyboard.write_section(
+        "alphabet/consonant doubles".to_string(),
+        alphabet_doubles_map,
+    );
+
+    // final consonants
+    let mut final_consonants_map = KbdMap::new();
+    for (base_letter, final_letter) in final_consonants {
+        for (key_seq, letter) in consonants.iter() {
+            if base_letter == *letter {
+                let mut new_seq: String = (*key_seq).to_string();
+                new_seq.push(' ');
+                final_consonants_map.add(new_seq, [final_letter, ' '].iter().collect());
+            }
+        }
+    }
+    keyboard.write_section("final consonants".to_string(), final_consonants_map);
+
+    // punctuation
+    let mut punctuation_map = KbdMap::new();
+    for (punct_letter, letter) in punctuation {
+        punctuation_map.add(
+            [modifier_special, punct_letter].iter().collect(),
+            letter.to_string(),
+        );
+    }
+    keyboard.write_section("punctuation".to_string(), punctuation_map);
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn gen(keyboard: &mut KbdWriter) {
     fn gen_vowels(
         map: &mut KbdMap,
-        modifiers: Vec<&str>,
+        modifiers: Vec<Diacritic>,
         vowel_key_short: &[char],
         short_vowels: &[char],
         vowel_key_long: &[char],
         long_vowels: &[char],
-        modifier_map: &HashMap<&str, (char, Option<&str>)>,
-        compositions: &CompositionMap,
+        modifier_map: &HashMap<Diacritic, char>,
         short_or_long_selector: Option<(bool, bool)>,
         capitals: bool,
     ) {
@@ -27,11 +143,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
         let (short, long) = short_or_long_selector.unwrap_or((true, true));
         let modifier_keys: VecDeque<char> = modifiers
             .iter()
-            .map(|str| modifier_map.get(str).unwrap().0)
-            .collect();
-        let modifier_strs: VecDeque<&str> = modifiers
-            .iter()
-            .filter_map(|str| modifier_map.get(str).unwrap().1)
+            .map(|diacritic| *modifier_map.get(diacritic).unwrap())
             .collect();
         if short {
             if capitals {
@@ -47,8 +159,8 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 modifier.push_front(local_vowel_key_short[index]);
 
                 let mut output: Vec<char> = vec![*vowel];
-                for modifier in modifier_strs.iter() {
-                    output.push(compositions.above(modifier));
+                for modifier in modifiers.iter() {
+                    output.push(modifier.char());
                 }
                 map.add(
                     modifier.iter().collect(),
@@ -67,12 +179,12 @@ pub fn gen(keyboard: &mut KbdWriter) {
             }
             for (index, vowel) in local_long_vowels.iter().enumerate() {
                 let mut modifier = modifier_keys.clone();
-                modifier.push_front(modifier_map.get("macron").unwrap().0);
+                modifier.push_front(*modifier_map.get(&Diacritic::Macron).unwrap());
                 modifier.push_front(local_vowel_key_long[index]);
 
                 let mut output: Vec<char> = vec![*vowel];
-                for modifier in modifier_strs.iter() {
-                    output.push(compositions.above(modifier));
+                for modifier in modifiers.iter() {
+                    output.push(modifier.char());
                 }
                 map.add(
                     modifier.iter().collect(),
@@ -84,13 +196,12 @@ pub fn gen(keyboard: &mut KbdWriter) {
     #[allow(clippy::too_many_arguments)]
     fn gen_vowels_both(
         map: &mut KbdMap,
-        modifiers: Vec<&str>,
+        modifiers: Vec<Diacritic>,
         vowel_key_short: &[char],
         short_vowels: &[char],
         vowel_key_long: &[char],
         long_vowels: &[char],
-        modifier_map: &HashMap<&str, (char, Option<&str>)>,
-        compositions: &CompositionMap,
+        modifier_map: &HashMap<Diacritic, char>,
         short_or_long_selector: Option<(bool, bool)>,
     ) {
         gen_vowels(
@@ -101,7 +212,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
             vowel_key_long,
             long_vowels,
             modifier_map,
-            compositions,
             short_or_long_selector,
             false,
         );
@@ -113,7 +223,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
             vowel_key_long,
             long_vowels,
             modifier_map,
-            compositions,
             short_or_long_selector,
             true,
         );
@@ -122,7 +231,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
     #[allow(clippy::too_many_arguments)]
     fn gen_class(
         map: &mut KbdMap,
-        base_modifiers: Vec<&str>,
+        base_modifiers: Vec<Diacritic>,
         vowel_key: &[char],
         vowel_key_ambiguous: &[char],
         vowel_key_iotable_ambiguous: &[char],
@@ -132,8 +241,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
         vowel_key_iotable_long: &[char],
         long_vowels: &[char],
         iotable_vowels_long: &[char],
-        modifier_map: &HashMap<&str, (char, Option<&str>)>,
-        compositions: &CompositionMap,
+        modifier_map: &HashMap<Diacritic, char>,
         iotable: bool,
         capitals: bool,
     ) {
@@ -145,7 +253,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
             vowel_key,
             long_vowels,
             modifier_map,
-            compositions,
             None,
             capitals,
         );
@@ -154,7 +261,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 map,
                 base_modifiers
                     .iter()
-                    .chain(vec!["iota"].iter())
+                    .chain([Diacritic::Ypogegrammeni].iter())
                     .cloned()
                     .collect(),
                 vowel_key_iotable_ambiguous,
@@ -162,7 +269,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 vowel_key_iotable_long,
                 iotable_vowels_long,
                 modifier_map,
-                compositions,
                 None,
                 capitals,
             );
@@ -171,7 +277,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
             map,
             base_modifiers
                 .iter()
-                .chain(vec!["acute"].iter())
+                .chain([Diacritic::Acute].iter())
                 .cloned()
                 .collect(),
             vowel_key,
@@ -179,7 +285,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
             vowel_key,
             long_vowels,
             modifier_map,
-            compositions,
             None,
             capitals,
         );
@@ -188,7 +293,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 map,
                 base_modifiers
                     .iter()
-                    .chain(vec!["acute", "iota"].iter())
+                    .chain([Diacritic::Acute, Diacritic::Ypogegrammeni].iter())
                     .cloned()
                     .collect(),
                 vowel_key_iotable_ambiguous,
@@ -196,7 +301,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 vowel_key_iotable_long,
                 iotable_vowels_long,
                 modifier_map,
-                compositions,
                 None,
                 capitals,
             );
@@ -205,7 +309,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
             map,
             base_modifiers
                 .iter()
-                .chain(vec!["grave"].iter())
+                .chain([Diacritic::Grave].iter())
                 .cloned()
                 .collect(),
             vowel_key,
@@ -213,7 +317,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
             vowel_key,
             long_vowels,
             modifier_map,
-            compositions,
             None,
             capitals,
         );
@@ -222,7 +325,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 map,
                 base_modifiers
                     .iter()
-                    .chain(vec!["grave", "iota"].iter())
+                    .chain([Diacritic::Grave, Diacritic::Ypogegrammeni].iter())
                     .cloned()
                     .collect(),
                 vowel_key_iotable_ambiguous,
@@ -230,7 +333,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 vowel_key_iotable_long,
                 iotable_vowels_long,
                 modifier_map,
-                compositions,
                 None,
                 capitals,
             );
@@ -239,7 +341,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
             map,
             base_modifiers
                 .iter()
-                .chain(vec!["circumflex"].iter())
+                .chain([Diacritic::Perispomeni].iter())
                 .cloned()
                 .collect(),
             vowel_key_ambiguous,
@@ -247,7 +349,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
             vowel_key,
             long_vowels,
             modifier_map,
-            compositions,
             None,
             capitals,
         );
@@ -256,7 +357,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 map,
                 base_modifiers
                     .iter()
-                    .chain(vec!["circumflex", "iota"].iter())
+                    .chain([Diacritic::Perispomeni, Diacritic::Ypogegrammeni].iter())
                     .cloned()
                     .collect(),
                 vowel_key_iotable_ambiguous,
@@ -264,7 +365,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
                 vowel_key_iotable_long,
                 iotable_vowels_long,
                 modifier_map,
-                compositions,
                 None,
                 capitals,
             );
@@ -274,7 +374,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
     #[allow(clippy::too_many_arguments)]
     fn gen_class_both(
         map: &mut KbdMap,
-        base_modifiers: Vec<&str>,
+        base_modifiers: Vec<Diacritic>,
         vowel_key: &[char],
         vowel_key_ambiguous: &[char],
         vowel_key_iotable_ambiguous: &[char],
@@ -284,8 +384,7 @@ pub fn gen(keyboard: &mut KbdWriter) {
         vowel_key_iotable_long: &[char],
         long_vowels: &[char],
         iotable_vowels_long: &[char],
-        modifier_map: &HashMap<&str, (char, Option<&str>)>,
-        compositions: &CompositionMap,
+        modifier_map: &HashMap<Diacritic, char>,
         iotable: bool,
     ) {
         gen_class(
@@ -301,7 +400,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
             long_vowels,
             iotable_vowels_long,
             modifier_map,
-            compositions,
             iotable,
             false,
         );
@@ -318,12 +416,10 @@ pub fn gen(keyboard: &mut KbdWriter) {
             long_vowels,
             iotable_vowels_long,
             modifier_map,
-            compositions,
             iotable,
             true,
         );
     }
-    let compositions = CompositionMap::new();
 
     keyboard.set_metadata(KbdMetaData::new(
         "Ancient Greek".to_string(),
@@ -354,51 +450,21 @@ pub fn gen(keyboard: &mut KbdWriter) {
         modifier_diaresis,
         modifier_breve,
     ];
-    let modifier_map: HashMap<&str, (char, Option<&str>)> = HashMap::from([
-        ("special", (modifier_special, None)),
-        ("macron", (modifier_macron, Some("macron"))),
-        ("acute", (modifier_acute, Some("acute"))),
-        ("grave", (modifier_grave, Some("grave"))),
-        ("circumflex", (modifier_circumflex, Some("periposmeni"))),
-        ("smooth", (modifier_smooth, Some("comma"))),
-        ("rough", (modifier_rough, Some("reversed comma"))),
-        ("iota", (modifier_iota, Some("iota"))),
-        ("diaresis", (modifier_diaresis, Some("diaresis"))),
-        ("breve", (modifier_breve, Some("breve"))),
-    ]);
-
-    let consonants: HashMap<&str, char> = HashMap::from([
-        ("b", 'β'),
-        ("g", 'γ'),
-        ("d", 'δ'),
-        ("z", 'ζ'),
-        ("th", 'θ'),
-        ("k", 'κ'),
-        ("l", 'λ'),
-        ("m", 'μ'),
-        ("n", 'ν'),
-        ("ks", 'ξ'),
-        ("p", 'π'),
-        ("r", 'ρ'),
-        ("s", 'σ'),
-        ("t", 'τ'),
-        ("ph", 'φ'),
-        ("kh", 'χ'),
-        ("ps", 'ψ'),
+    let modifier_map: HashMap<Diacritic, char> = HashMap::from([
+        (Diacritic::Macron, modifier_macron),
+        (Diacritic::Acute, modifier_acute),
+        (Diacritic::Grave, modifier_grave),
+        (Diacritic::Perispomeni, modifier_circumflex),
+        (Diacritic::Psili, modifier_smooth),
+        (Diacritic::Dasia, modifier_rough),
+        (Diacritic::Ypogegrammeni, modifier_iota),
+        (Diacritic::Diaresis, modifier_diaresis),
+        (Diacritic::Breve, modifier_breve),
     ]);
-    let alphabet_doubles: Vec<(String, char)> = vec![
-        ("h".to_string(), 'η'), // non-phonetic
-        ("c".to_string(), 'κ'),
-        ("f".to_string(), 'φ'),
-        ("ch".to_string(), 'χ'),
-        ("x".to_string(), 'ξ'),
-        ("v".to_string(), 'ω'), // non-phonetic
-    ];
-    let final_consonants: Vec<(char, char)> = vec![('σ', 'ς')];
-
-    let vowel_key: Vec<char> = vec!['a', 'e', 'i', 'o', 'u'];
-    let short_vowels: Vec<char> = vec!['α', 'ε', 'ι', 'ο', 'υ'];
-    let long_vowels: Vec<char> = vec!['ᾱ', 'η', 'ῑ', 'ω', 'ῡ'];
+    keyboard.add_modifier(modifier_special, "special".to_string());
+    for (diacritic, key) in modifier_map.iter() {
+        keyboard.add_modifier(*key, diacritic.name().to_string());
+    }
 
     let vowel_key_ambiguous: Vec<char> = vec!['a', 'i', 'u'];
     let ambiguous_vowels: Vec<char> = vec!['α', 'ι', 'υ'];
@@ -413,102 +479,23 @@ pub fn gen(keyboard: &mut KbdWriter) {
     let diaresis_vowels: Vec<char> = vec!['ι', 'υ'];
     let diaresis_long_vowels: Vec<char> = vec!['ῑ', 'ῡ'];
 
-    let punctuation: Vec<(char, char)> = vec![('.', '·'), ('<', '«'), ('>', '»'), ('?', ';')];
-
-    // basic alphabet
-    let mut alphabet_map = KbdMap::new();
-    for (sequence, letter) in consonants.iter() {
-        // lowercase
-        alphabet_map.add(sequence.to_string(), letter.to_string());
-        // capital
-        let mut cap_seq = sequence.to_string().to_uppercase();
-        let cap_letter = letter.to_uppercase().to_string();
-        if cap_seq.len() > 1 {
-            let last = sequence.chars().nth(1).unwrap();
-            //println!("long: {} -> {}, last char: {}", sequence, letter, last);
-            // if the 2nd character is not a modifier
-            if modifiers.contains(&last) {
-                //println!("  modifiers contains {}", last);
-                cap_seq.pop();
-                cap_seq.push(last);
-                alphabet_map.add(cap_seq, cap_letter);
-            } else {
-                alphabet_map.add(cap_seq, cap_letter);
-            }
-        } else {
-            alphabet_map.add(cap_seq, cap_letter);
-        }
-    }
-    keyboard.write_section("consonants".to_string(), alphabet_map);
-
-    // doubled alphabet
-    let mut alphabet_doubles_map = KbdMap::new();
-    for (sequence, letter) in alphabet_doubles {
-        // lowercase
-        alphabet_doubles_map.add(sequence.clone(), letter.to_string());
-        // capital
-        let mut cap_seq = sequence.to_uppercase();
-        let cap_letter = letter.to_uppercase().to_string();
-        if cap_seq.len() > 1 {
-            let last = sequence.chars().nth(1).unwrap();
-            //println!("long: {} -> {}, last char: {}", sequence, letter, last);
-            // if the 2nd character is not a modifier
-            if modifiers.contains(&last) {
-                //println!("  modifiers contains {}", last);
-                cap_seq.pop();
-                cap_seq.push(last);
-                alphabet_doubles_map.add(cap_seq, cap_letter);
-            } else {
-                alphabet_doubles_map.add(cap_seq, cap_letter);
-            }
-        } else {
-            alphabet_doubles_map.add(cap_seq, cap_letter);
-        }
-    }
-    keyboard.write_section(
-        "alphabet/consonant doubles".to_string(),
-        alphabet_doubles_map,
-    );
-
-    // final consonants
-    let mut final_consonants_map = KbdMap::new();
-    for (base_letter, final_letter) in final_consonants {
-        for (key_seq, letter) in consonants.iter() {
-            if base_letter == *letter {
-                let mut new_seq: String = (*key_seq).to_string();
-                new_seq.push(' ');
-                final_consonants_map.add(new_seq, [final_letter, ' '].iter().collect());
-            }
-        }
-    }
-    keyboard.write_section("final consonants".to_string(), final_consonants_map);
-
-    // punctuation
-    let mut punctuation_map = KbdMap::new();
-    for (punct_letter, letter) in punctuation {
-        punctuation_map.add(
-            vec![modifier_special, punct_letter].iter().collect(),
-            letter.to_string(),
-        );
-    }
-    keyboard.write_section("punctuation".to_string(), punctuation_map);
+    write_alphabet(keyboard, &modifiers, modifier_special);
 
     // accents
     let mut accents_map: KbdMap = KbdMap::new();
     gen_class_both(
         &mut accents_map,
         vec![],
-        &vowel_key,
+        VOWEL_KEY,
         &vowel_key_ambiguous,
         &vowel_key_iotable_ambiguous,
-        &short_vowels,
+        SHORT_VOWELS,
         &ambiguous_vowels,
         &iotable_vowels_ambiguous,
         &vowel_key_iotable_long,
-        &long_vowels,
+        LONG_VOWELS,
         &iotable_vowels_long,
         &modifier_map,
-        &compositions,
         true,
     );
 
@@ -518,19 +505,18 @@ pub fn gen(keyboard: &mut KbdWriter) {
     // breve
     gen_vowels_both(
         &mut accents_nobreath_map,
-        vec!["breve"],
+        vec![Diacritic::Breve],
         &vowel_key_ambiguous,
         &ambiguous_vowels,
         &vowel_key_ambiguous,
         &ambiguous_vowels,
         &modifier_map,
-        &compositions,
         Some((true, false)),
     );
     // diaresis
     gen_class_both(
         &mut accents_nobreath_map,
-        vec!["diaresis"],
+        vec![Diacritic::Diaresis],
         &vowel_key_diaresis,
         &vowel_key_diaresis,
         &vowel_key_iotable_ambiguous,
@@ -541,7 +527,6 @@ pub fn gen(keyboard: &mut KbdWriter) {
         &diaresis_long_vowels,
         &iotable_vowels_long,
         &modifier_map,
-        &compositions,
         false,
     );
     keyboard.write_section(
@@ -553,18 +538,17 @@ pub fn gen(keyboard: &mut KbdWriter) {
     let mut smooth_breathing_map = KbdMap::new();
     gen_class_both(
         &mut smooth_breathing_map,
-        vec!["smooth"],
-        &vowel_key,
+        vec![Diacritic::Psili],
+        VOWEL_KEY,
         &vowel_key_ambiguous,
         &vowel_key_iotable_ambiguous,
-        &short_vowels,
+        SHORT_VOWELS,
         &ambiguous_vowels,
         &iotable_vowels_ambiguous,
         &vowel_key_iotable_long,
-        &long_vowels,
+        LONG_VOWELS,
         &iotable_vowels_long,
         &modifier_map,
-        &compositions,
         true,
     );
     keyboard.write_section("smooth breathing".to_string(), smooth_breathing_map);
@@ -573,19 +557,157 @@ pub fn gen(keyboard: &mut KbdWriter) {
     let mut rough_breathing_map = KbdMap::new();
     gen_class_both(
         &mut rough_breathing_map,
-        vec!["rough"],
-        &vowel_key,
+        vec![Diacritic::Dasia],
+        VOWEL_KEY,
         &vowel_key_ambiguous,
         &vowel_key_iotable_ambiguous,
-        &short_vowels,
+        SHORT_VOWELS,
         &ambiguous_vowels,
         &iotable_vowels_ambiguous,
         &vowel_key_iotable_long,
-        &long_vowels,
+        LONG_VOWELS,
         &iotable_vowels_long,
         &modifier_map,
-        &compositions,
         true,
     );
     keyboard.write_section("rough breathing".to_string(), rough_breathing_map);
 }
+
+/// Modern Greek, the acute is mapped to the tonos code points by NFC
+pub fn gen_monotonic(keyboard: &mut KbdWriter) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Modern Greek".to_string(),
+        "el".to_string(),
+        "Monotonic Modern Greek with tonos and dialytika".to_string(),
+    ));
+
+    // modifier keys
+    let modifier_special: char = 'q';
+    let modifier_tonos: char = ';';
+    let modifier_dialytika: char = '{';
+    let modifiers: Vec<char> = vec![modifier_special, modifier_tonos, modifier_dialytika];
+    keyboard.add_modifier(modifier_special, "special".to_string());
+    keyboard.add_modifier(modifier_tonos, "tonos".to_string());
+    keyboard.add_modifier(modifier_dialytika, "dialytika".to_string());
+
+    write_alphabet(keyboard, &modifiers, modifier_special);
+
+    // the short vowels, and η and ω from their doubled letters
+    let mut vowels: Vec<(char, char)> = VOWEL_KEY
+        .iter()
+        .cloned()
+        .zip(SHORT_VOWELS.iter().cloned())
+        .collect();
+    vowels.push(('h', 'η'));
+    vowels.push(('v', 'ω'));
+    let diaresis_vowels: Vec<char> = vec!['ι', 'υ'];
+
+    // vowels without any mark, η and ω are in the doubled alphabet
+    let mut vowel_map = KbdMap::new();
+    for (key, vowel) in VOWEL_KEY.iter().zip(SHORT_VOWELS.iter()) {
+        vowel_map.add(key.to_string(), vowel.to_string());
+        vowel_map.add(
+            key.to_ascii_uppercase().to_string(),
+            vowel.to_uppercase().to_string(),
+        );
+    }
+    keyboard.write_section("vowels".to_string(), vowel_map);
+
+    // tonos
+    let mut tonos_map = KbdMap::new();
+    for (key, vowel) in vowels.iter() {
+        for capital in [false, true] {
+            let (key, vowel) = match capital {
+                true => (
+                    key.to_ascii_uppercase(),
+                    vowel.to_uppercase().next().unwrap(),
+                ),
+                false => (*key, *vowel),
+            };
+            tonos_map.add(
+                [key, modifier_tonos].iter().collect(),
+                compose_vec(vec![vowel, Diacritic::Acute.char()])
+                    .iter()
+                    .collect(),
+            );
+        }
+    }
+    keyboard.write_section("tonos".to_string(), tonos_map);
+
+    // dialytika, with a tonos on the lowercase vowels only
+    let mut dialytika_map = KbdMap::new();
+    for (key, vowel) in vowels.iter() {
+        if !diaresis_vowels.contains(vowel) {
+            continue;
+        }
+        for capital in [false, true] {
+            let (key, vowel) = match capital {
+                true => (
+                    key.to_ascii_uppercase(),
+                    vowel.to_uppercase().next().unwrap(),
+                ),
+                false => (*key, *vowel),
+            };
+            dialytika_map.add(
+                [key, modifier_dialytika].iter().collect(),
+                compose_vec(vec![vowel, Diacritic::Diaresis.char()])
+                    .iter()
+                    .collect(),
+            );
+            if !capital {
+                dialytika_map.add(
+                    [key, modifier_dialytika, modifier_tonos].iter().collect(),
+                    compose_vec(vec![
+                        vowel,
+                        Diacritic::Diaresis.char(),
+                        Diacritic::Acute.char(),
+                    ])
+                    .iter()
+                    .collect(),
+                );
+            }
+        }
+    }
+    keyboard.write_section("dialytika".to_string(), dialytika_map);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monotonic() {
+        let mut kbd = KbdWriter::new();
+        gen_monotonic(&mut kbd);
+        let sections: Vec<&String> = kbd.sections.iter().map(|(name, _)| name).collect();
+        assert!(!sections.contains(&&"smooth breathing".to_string()));
+        let (_, vowels) = kbd
+            .sections
+            .iter()
+            .find(|(name, _)| name == "vowels")
+            .unwrap();
+        let mapped: Vec<(&String, &String)> = vowels.iter().collect();
+        assert!(mapped.contains(&(&"a".to_string(), &"α".to_string())));
+        assert!(mapped.contains(&(&"A".to_string(), &"Α".to_string())));
+        let (_, tonos) = kbd
+            .sections
+            .iter()
+            .find(|(name, _)| name == "tonos")
+            .unwrap();
+        let mapped: Vec<(&String, &String)> = tonos.iter().collect();
+        assert!(mapped.contains(&(&"a;".to_string(), &"\u{3ac}".to_string())));
+        assert!(mapped.contains(&(&"V;".to_string(), &"\u{38f}".to_string())));
+        assert!(mapped
+            .iter()
+            .all(|(_, vowel)| !('\u{1f00}'..='\u{1fff}').contains(&vowel.chars().next().unwrap())));
+        let (_, dialytika) = kbd
+            .sections
+            .iter()
+            .find(|(name, _)| name == "dialytika")
+            .unwrap();
+        assert_eq!(
+            dialytika.iter().find(|(seq, _)| *seq == "i{;").unwrap().1,
+            "\u{390}"
+        );
+    }
+}
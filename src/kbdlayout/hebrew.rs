@@ -0,0 +1,193 @@
+use crate::{
+    compose::Diacritic,
+    kbdwriter::{KbdMap, KbdMetaData, KbdWriter},
+};
+
+pub fn gen(keyboard: &mut KbdWriter, cantillation: bool) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Hebrew".to_string(),
+        "he".to_string(),
+        match cantillation {
+            true => "Hebrew phonetic layout with niqqud and cantillation marks".to_string(),
+            false => "Hebrew phonetic layout with niqqud".to_string(),
+        },
+    ));
+
+    // modifier keys
+    let modifier_point: char = ';';
+    let modifier_accent: char = '`';
+    keyboard.add_modifier(modifier_point, "niqqud".to_string());
+    keyboard.add_modifier(
+        modifier_accent,
+        match cantillation {
+            true => "cantillation".to_string(),
+            false => "punctuation".to_string(),
+        },
+    );
+
+    let consonants: Vec<(char, char)> = vec![
+        ('a', 'א'),
+        ('b', 'ב'),
+        ('g', 'ג'),
+        ('d', 'ד'),
+        ('h', 'ה'),
+        ('v', 'ו'),
+        ('z', 'ז'),
+        ('x', 'ח'),
+        ('T', 'ט'),
+        ('y', 'י'),
+        ('k', 'כ'),
+        ('l', 'ל'),
+        ('m', 'מ'),
+        ('n', 'נ'),
+        ('s', 'ס'),
+        ('e', 'ע'),
+        ('p', 'פ'),
+        ('c', 'צ'),
+        ('q', 'ק'),
+        ('r', 'ר'),
+        ('w', 'ש'),
+        ('t', 'ת'),
+    ];
+    let final_consonants: Vec<(char, char)> =
+        vec![('כ', 'ך'), ('מ', 'ם'), ('נ', 'ן'), ('פ', 'ף'), ('צ', 'ץ')];
+    let word_boundaries: Vec<char> = vec![' ', ',', '.'];
+
+    let points: Vec<(&str, Diacritic)> = vec![
+        (":", Diacritic::Sheva),
+        (":e", Diacritic::HatafSegol),
+        (":a", Diacritic::HatafPatah),
+        (":o", Diacritic::HatafQamats),
+        ("i", Diacritic::Hiriq),
+        ("E", Diacritic::Tsere),
+        ("e", Diacritic::Segol),
+        ("a", Diacritic::Patah),
+        ("A", Diacritic::Qamats),
+        ("o", Diacritic::Holam),
+        ("u", Diacritic::Qubuts),
+        (".", Diacritic::Dagesh),
+        ("-", Diacritic::Rafe),
+        ("s", Diacritic::ShinDot),
+        ("S", Diacritic::SinDot),
+        ("O", Diacritic::QamatsQatan),
+    ];
+
+    let accents: Vec<(char, char)> = vec![
+        ('e', '\u{591}'), // etnahta
+        ('s', '\u{592}'), // segol
+        ('q', '\u{594}'), // zaqef qatan
+        ('Q', '\u{595}'), // zaqef gadol
+        ('t', '\u{596}'), // tipeha
+        ('r', '\u{597}'), // revia
+        ('z', '\u{598}'), // zarqa
+        ('p', '\u{599}'), // pashta
+        ('y', '\u{59a}'), // yetiv
+        ('v', '\u{59b}'), // tevir
+        ('g', '\u{59c}'), // geresh
+        ('G', '\u{59e}'), // gershayim
+        ('L', '\u{5a0}'), // telisha gedola
+        ('f', '\u{5a1}'), // pazer
+        ('m', '\u{5a3}'), // munah
+        ('h', '\u{5a4}'), // mahapakh
+        ('k', '\u{5a5}'), // merkha
+        ('K', '\u{5a6}'), // merkha kefula
+        ('d', '\u{5a7}'), // darga
+        ('a', '\u{5a8}'), // qadma
+        ('l', '\u{5a9}'), // telisha qetana
+        ('M', '\u{5bd}'), // meteg
+    ];
+    let punctuation: Vec<(char, char)> = vec![
+        ('-', '\u{5be}'),  // maqaf
+        ('|', '\u{5c0}'),  // paseq
+        (':', '\u{5c3}'),  // sof pasuq
+        ('\'', '\u{5f3}'), // geresh
+        ('\"', '\u{5f4}'), // gershayim
+    ];
+
+    // consonants
+    let mut consonant_map = KbdMap::new();
+    for (key, letter) in consonants.iter() {
+        consonant_map.add(key.to_string(), letter.to_string());
+    }
+    keyboard.write_section("consonants".to_string(), consonant_map);
+
+    // final forms, at the end of a word, with the niqqud typed before the word boundary
+    let mut final_consonants_map = KbdMap::new();
+    for (base_letter, final_letter) in final_consonants.iter() {
+        for (key, letter) in consonants.iter() {
+            if base_letter == letter {
+                for point in std::iter::once(None).chain(points.iter().map(Some)) {
+                    let (point_keys, point_mark) = match point {
+                        Some((keys, point)) => {
+                            (modifier_point.to_string() + keys, point.char().to_string())
+                        }
+                        None => (String::new(), String::new()),
+                    };
+                    for boundary in word_boundaries.iter() {
+                        final_consonants_map.add(
+                            format!("{}{}{}", key, point_keys, boundary),
+                            format!("{}{}{}", final_letter, point_mark, boundary),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    keyboard.write_section("final consonants".to_string(), final_consonants_map);
+
+    // niqqud, typed after the consonant they belong to
+    let mut point_map = KbdMap::new();
+    for (keys, point) in points.iter() {
+        let mut seq = modifier_point.to_string();
+        seq.push_str(keys);
+        point_map.add(seq, point.char().to_string());
+    }
+    keyboard.write_section("niqqud".to_string(), point_map);
+
+    // punctuation
+    let mut punctuation_map = KbdMap::new();
+    for (key, mark) in punctuation.iter() {
+        punctuation_map.add([modifier_accent, *key].iter().collect(), mark.to_string());
+    }
+    keyboard.write_section("punctuation".to_string(), punctuation_map);
+
+    // cantillation
+    if cantillation {
+        let mut accent_map = KbdMap::new();
+        for (key, accent) in accents.iter() {
+            accent_map.add([modifier_accent, *key].iter().collect(), accent.to_string());
+        }
+        keyboard.write_section("cantillation".to_string(), accent_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn final_forms() {
+        let mut kbd = KbdWriter::new();
+        gen(&mut kbd, false);
+        assert_eq!(kbd.lookup("k "), Some("ך "));
+        assert_eq!(kbd.lookup("m."), Some("ם."));
+        assert_eq!(kbd.lookup("c,"), Some("ץ,"));
+        assert_eq!(kbd.lookup("k;A "), Some("ךָ "));
+        assert_eq!(kbd.lookup("k;: "), Some("ךְ "));
+        assert_eq!(kbd.lookup("k;.."), Some("ךּ."));
+        assert_eq!(kbd.lookup("b "), None);
+        assert_eq!(kbd.lookup(";a "), None);
+    }
+
+    #[test]
+    fn niqqud() {
+        let mut kbd = KbdWriter::new();
+        gen(&mut kbd, false);
+        assert_eq!(kbd.lookup(";a"), Some("\u{5b7}"));
+        assert_eq!(kbd.lookup(";A"), Some("\u{5b8}"));
+        assert_eq!(kbd.lookup(";:"), Some("\u{5b0}"));
+        assert_eq!(kbd.lookup(";:a"), Some("\u{5b2}"));
+        assert_eq!(kbd.lookup(";."), Some("\u{5bc}"));
+        assert_eq!(kbd.lookup(";s"), Some("\u{5c1}"));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    compose::Diacritic,
+    kbdwriter::{KbdMap, KbdMetaData, KbdWriter},
+};
+
+pub fn gen(keyboard: &mut KbdWriter) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "International Phonetic Alphabet".to_string(),
+        "und-fonipa".to_string(),
+        "IPA typed with X-SAMPA inspired sequences".to_string(),
+    ));
+
+    // modifier keys
+    let modifier_variant: char = '\\';
+    let modifier_retroflex: char = '`';
+    let modifier_diacritic: char = '_';
+    // on its own ^ is the upstep, followed by a digit it types a tone letter
+    let modifier_tone: char = '^';
+    keyboard.add_modifier(modifier_variant, "variant".to_string());
+    keyboard.add_modifier(modifier_retroflex, "retroflex".to_string());
+    keyboard.add_modifier(modifier_diacritic, "diacritic".to_string());
+    keyboard.add_modifier(modifier_tone, "tone letter".to_string());
+
+    // letters that type themselves are left out
+    let pulmonic_consonants: Vec<(&str, char)> = vec![
+        // plosives
+        ("t`", 'ʈ'),
+        ("d`", 'ɖ'),
+        ("J\\", 'ɟ'),
+        ("G\\", 'ɢ'),
+        ("?", 'ʔ'),
+        // nasals
+        ("F", 'ɱ'),
+        ("n`", 'ɳ'),
+        ("J", 'ɲ'),
+        ("N", 'ŋ'),
+        ("N\\", 'ɴ'),
+        // trills
+        ("B\\", 'ʙ'),
+        ("R\\", 'ʀ'),
+        // taps and flaps
+        ("v\\", 'ⱱ'),
+        ("4", 'ɾ'),
+        ("r`", 'ɽ'),
+        // fricatives
+        ("p\\", 'ɸ'),
+        ("B", 'β'),
+        ("T", 'θ'),
+        ("D", 'ð'),
+        ("S", 'ʃ'),
+        ("Z", 'ʒ'),
+        ("s`", 'ʂ'),
+        ("z`", 'ʐ'),
+        ("C", 'ç'),
+        ("j\\", 'ʝ'),
+        ("G", 'ɣ'),
+        ("X", 'χ'),
+        ("R", 'ʁ'),
+        ("X\\", 'ħ'),
+        ("?\\", 'ʕ'),
+        ("h\\", 'ɦ'),
+        // lateral fricatives
+        ("K", 'ɬ'),
+        ("K\\", 'ɮ'),
+        // approximants
+        ("P", 'ʋ'),
+        ("r\\", 'ɹ'),
+        ("r\\`", 'ɻ'),
+        ("M\\", 'ɰ'),
+        // lateral approximants
+        ("l`", 'ɭ'),
+        ("L", 'ʎ'),
+        ("L\\", 'ʟ'),
+    ];
+    let non_pulmonic_consonants: Vec<(&str, char)> = vec![
+        // clicks
+        ("O\\", 'ʘ'),
+        ("|\\", 'ǀ'),
+        ("!\\", 'ǃ'),
+        ("=\\", 'ǂ'),
+        ("|\\|\\", 'ǁ'),
+        // implosives
+        ("b_<", 'ɓ'),
+        ("d_<", 'ɗ'),
+        ("J\\_<", 'ʄ'),
+        ("g_<", 'ɠ'),
+        ("G\\_<", 'ʛ'),
+    ];
+    let other_symbols: Vec<(&str, char)> = vec![
+        ("W", 'ʍ'),
+        ("H", 'ɥ'),
+        ("H\\", 'ʜ'),
+        ("<\\", 'ʢ'),
+        (">\\", 'ʡ'),
+        ("s\\", 'ɕ'),
+        ("z\\", 'ʑ'),
+        ("l\\", 'ɺ'),
+        ("x\\", 'ɧ'),
+        ("g", 'ɡ'),
+    ];
+    let vowels: Vec<(&str, char)> = vec![
+        // close
+        ("1", 'ɨ'),
+        ("}", 'ʉ'),
+        ("M", 'ɯ'),
+        // near-close
+        ("I", 'ɪ'),
+        ("Y", 'ʏ'),
+        ("U", 'ʊ'),
+        // close-mid
+        ("2", 'ø'),
+        ("@\\", 'ɘ'),
+        ("8", 'ɵ'),
+        ("7", 'ɤ'),
+        // mid
+        ("@", 'ə'),
+        // open-mid
+        ("E", 'ɛ'),
+        ("9", 'œ'),
+        ("3", 'ɜ'),
+        ("3\\", 'ɞ'),
+        ("V", 'ʌ'),
+        ("O", 'ɔ'),
+        // near-open
+        ("{", 'æ'),
+        ("6", 'ɐ'),
+        // open
+        ("&", 'ɶ'),
+        ("A", 'ɑ'),
+        ("Q", 'ɒ'),
+    ];
+    let suprasegmentals: Vec<(&str, char)> = vec![
+        ("\"", 'ˈ'),
+        ("%", 'ˌ'),
+        (":", Diacritic::TriangularColon.char()),
+        (":\\", Diacritic::HalfTriangularColon.char()),
+        ("_X", Diacritic::Breve.char()),
+        ("||", '‖'),
+        ("-\\", '‿'),
+        ("!", 'ꜜ'),
+        ("^", 'ꜛ'),
+        ("<R>", '↗'),
+        ("<F>", '↘'),
+    ];
+    // Chao tone letters, 5 is the highest
+    let tone_letters: Vec<(char, char)> =
+        vec![('5', '˥'), ('4', '˦'), ('3', '˧'), ('2', '˨'), ('1', '˩')];
+    let diacritics: Vec<(&str, Diacritic)> = vec![
+        ("0", Diacritic::RingBelow),
+        ("v", Diacritic::CaronBelow),
+        ("h", Diacritic::ModifierH),
+        ("t", Diacritic::DiaresisBelow),
+        ("k", Diacritic::TildeBelow),
+        ("N", Diacritic::SeagullBelow),
+        ("O", Diacritic::RightHalfRingBelow),
+        ("c", Diacritic::LeftHalfRingBelow),
+        ("w", Diacritic::ModifierW),
+        ("j", Diacritic::ModifierJ),
+        ("G", Diacritic::ModifierGamma),
+        ("?\\", Diacritic::ModifierReversedGlottalStop),
+        ("e", Diacritic::TildeOverlay),
+        ("+", Diacritic::PlusSignBelow),
+        ("-", Diacritic::MinusSignBelow),
+        ("\"", Diacritic::Diaresis),
+        ("x", Diacritic::XAbove),
+        ("=", Diacritic::VerticalLineBelow),
+        ("^", Diacritic::InvertedBreveBelow),
+        ("`", Diacritic::RhoticHook),
+        ("r", Diacritic::UpTackBelow),
+        ("o", Diacritic::DownTackBelow),
+        ("A", Diacritic::LeftTackBelow),
+        ("q", Diacritic::RightTackBelow),
+        ("d", Diacritic::BridgeBelow),
+        ("a", Diacritic::InvertedBridgeBelow),
+        ("m", Diacritic::SquareBelow),
+        ("~", Diacritic::Tilde),
+        ("n", Diacritic::SuperscriptN),
+        ("l", Diacritic::ModifierL),
+        ("}", Diacritic::LeftAngleAbove),
+        (">", Diacritic::ModifierApostrophe),
+        (")", Diacritic::DoubleInvertedBreve),
+        // tones
+        ("T", Diacritic::DoubleAcute),
+        ("H", Diacritic::Acute),
+        ("M", Diacritic::Macron),
+        ("L", Diacritic::Grave),
+        ("B", Diacritic::DoubleGrave),
+        ("R", Diacritic::Caron),
+        ("F", Diacritic::Circumflex),
+    ];
+
+    for (name, symbols) in [
+        ("pulmonic consonants", pulmonic_consonants),
+        ("non-pulmonic consonants", non_pulmonic_consonants),
+        ("other symbols", other_symbols),
+        ("vowels", vowels),
+        ("suprasegmentals", suprasegmentals),
+    ] {
+        let mut map = KbdMap::new();
+        for (seq, symbol) in symbols {
+            map.add(seq.to_string(), symbol.to_string());
+        }
+        keyboard.write_section(name.to_string(), map);
+    }
+
+    // tone letters, a contour is typed as two letters in a row
+    let mut tone_map = KbdMap::new();
+    for (key, letter) in tone_letters.iter() {
+        tone_map.add([modifier_tone, *key].iter().collect(), letter.to_string());
+    }
+    keyboard.write_section("tone letters".to_string(), tone_map);
+
+    // diacritics, typed after the symbol they belong to
+    let mut diacritic_map = KbdMap::new();
+    for (keys, diacritic) in diacritics.iter() {
+        let mut seq = modifier_diacritic.to_string();
+        seq.push_str(keys);
+        diacritic_map.add(seq, diacritic.char().to_string());
+    }
+    keyboard.write_section("diacritics".to_string(), diacritic_map);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers() {
+        let mut kbd = KbdWriter::new();
+        gen(&mut kbd);
+        assert_eq!(kbd.lookup("t`"), Some("ʈ"));
+        assert_eq!(kbd.lookup("_h"), Some("ʰ"));
+        assert_eq!(kbd.lookup("^5"), Some("˥"));
+        assert_eq!(kbd.lookup("^1"), Some("˩"));
+        assert_eq!(kbd.lookup("^"), Some("ꜛ"));
+        assert_eq!(kbd.lookup("_^"), Some("\u{32f}"));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::kbdwriter::{KbdMap, KbdMetaData, KbdRule, KbdWriter};
+
+/// Initial consonants in syllable order, on the two-set keyboard
+const INITIALS: [(&str, char); 19] = [
+    ("r", 'ㄱ'),
+    ("R", 'ㄲ'),
+    ("s", 'ㄴ'),
+    ("e", 'ㄷ'),
+    ("E", 'ㄸ'),
+    ("f", 'ㄹ'),
+    ("a", 'ㅁ'),
+    ("q", 'ㅂ'),
+    ("Q", 'ㅃ'),
+    ("t", 'ㅅ'),
+    ("T", 'ㅆ'),
+    ("d", 'ㅇ'),
+    ("w", 'ㅈ'),
+    ("W", 'ㅉ'),
+    ("c", 'ㅊ'),
+    ("z", 'ㅋ'),
+    ("x", 'ㅌ'),
+    ("v", 'ㅍ'),
+    ("g", 'ㅎ'),
+];
+
+/// Vowels in syllable order, a compound vowel is typed as the two vowels it joins
+const MEDIALS: [(&str, char); 21] = [
+    ("k", 'ㅏ'),
+    ("o", 'ㅐ'),
+    ("i", 'ㅑ'),
+    ("O", 'ㅒ'),
+    ("j", 'ㅓ'),
+    ("p", 'ㅔ'),
+    ("u", 'ㅕ'),
+    ("P", 'ㅖ'),
+    ("h", 'ㅗ'),
+    ("hk", 'ㅘ'),
+    ("ho", 'ㅙ'),
+    ("hl", 'ㅚ'),
+    ("y", 'ㅛ'),
+    ("n", 'ㅜ'),
+    ("nj", 'ㅝ'),
+    ("np", 'ㅞ'),
+    ("nl", 'ㅟ'),
+    ("b", 'ㅠ'),
+    ("m", 'ㅡ'),
+    ("ml", 'ㅢ'),
+    ("l", 'ㅣ'),
+];
+
+/// Final consonants in syllable order, starting from 1 as 0 is no final
+const FINALS: [(&str, char); 27] = [
+    ("r", 'ㄱ'),
+    ("R", 'ㄲ'),
+    ("rt", 'ㄳ'),
+    ("s", 'ㄴ'),
+    ("sw", 'ㄵ'),
+    ("sg", 'ㄶ'),
+    ("e", 'ㄷ'),
+    ("f", 'ㄹ'),
+    ("fr", 'ㄺ'),
+    ("fa", 'ㄻ'),
+    ("fq", 'ㄼ'),
+    ("ft", 'ㄽ'),
+    ("fx", 'ㄾ'),
+    ("fv", 'ㄿ'),
+    ("fg", 'ㅀ'),
+    ("a", 'ㅁ'),
+    ("q", 'ㅂ'),
+    ("qt", 'ㅄ'),
+    ("t", 'ㅅ'),
+    ("T", 'ㅆ'),
+    ("d", 'ㅇ'),
+    ("w", 'ㅈ'),
+    ("c", 'ㅊ'),
+    ("z", 'ㅋ'),
+    ("x", 'ㅌ'),
+    ("v", 'ㅍ'),
+    ("g", 'ㅎ'),
+];
+
+/// Ends a syllable without a final, so the next consonant starts a syllable of its own
+const SYLLABLE_BREAK: char = '\'';
+
+/// The precomposed syllable of an initial, a medial and a final, 0 for no final
+pub fn syllable(initial: usize, medial: usize, final_consonant: usize) -> char {
+    let index = (initial * MEDIALS.len() + medial) * (FINALS.len() + 1) + final_consonant;
+    char::from_u32(0xac00 + index as u32).unwrap()
+}
+
+/// Splits the keys typed after a medial into a final followed by a vowel. The last consonant
+/// of the final starts the next syllable, what is left of it stays as the final
+fn carry_over(ending: &str) -> Option<(usize, usize, usize)> {
+    // vowel and consonant keys never overlap, so the vowel is the longest medial at the end
+    let (next_medial, (medial_keys, _)) = MEDIALS
+        .iter()
+        .enumerate()
+        .filter(|(_, (keys, _))| ending.len() > keys.len() && ending.ends_with(keys))
+        .max_by_key(|(_, (keys, _))| keys.len())?;
+    let final_keys = &ending[..ending.len() - medial_keys.len()];
+    if !FINALS.iter().any(|(keys, _)| *keys == final_keys) {
+        return None;
+    }
+    let (kept, carried) = final_keys.split_at(final_keys.len() - 1);
+    let final_consonant = match kept {
+        "" => 0,
+        _ => FINALS.iter().position(|(keys, _)| *keys == kept)? + 1,
+    };
+    let next_initial = INITIALS.iter().position(|(keys, _)| *keys == carried)?;
+    Some((final_consonant, next_initial, next_medial))
+}
+
+/// An initial, a medial and an optional final composed into one syllable, or into two when
+/// a vowel follows the final
+struct SyllableRule;
+
+impl KbdRule for SyllableRule {
+    fn inputs(&self) -> Vec<String> {
+        let endings: Vec<&str> = [""]
+            .into_iter()
+            .chain(FINALS.iter().map(|(keys, _)| *keys))
+            .collect();
+        let mut inputs = Vec::new();
+        for (initial, _) in INITIALS.iter() {
+            for (medial, _) in MEDIALS.iter() {
+                for ending in endings.iter() {
+                    inputs.push(format!("{}{}{}", initial, medial, ending));
+                }
+                inputs.push(format!("{}{}{}", initial, medial, SYLLABLE_BREAK));
+                // a vowel after the final carries its last consonant to the next syllable
+                for (final_keys, _) in FINALS.iter() {
+                    for (next_medial, _) in MEDIALS.iter() {
+                        inputs.push(format!(
+                            "{}{}{}{}",
+                            initial, medial, final_keys, next_medial
+                        ));
+                    }
+                }
+            }
+        }
+        inputs
+    }
+
+    fn apply(&self, seq: &str) -> Option<String> {
+        let initial = INITIALS
+            .iter()
+            .position(|(keys, _)| seq.starts_with(keys))?;
+        let rest = &seq[INITIALS[initial].0.len()..];
+        // vowel and final keys never overlap, so one medial at most leaves a valid ending
+        for (medial, (keys, _)) in MEDIALS.iter().enumerate() {
+            let ending = match rest.strip_prefix(keys) {
+                Some(ending) => ending,
+                None => continue,
+            };
+            let final_consonant = match ending {
+                "" => Some(0),
+                _ if ending == SYLLABLE_BREAK.to_string() => Some(0),
+                _ => FINALS
+                    .iter()
+                    .position(|(keys, _)| *keys == ending)
+                    .map(|index| index + 1),
+            };
+            if let Some(final_consonant) = final_consonant {
+                return Some(syllable(initial, medial, final_consonant).to_string());
+            }
+            if let Some((final_consonant, next_initial, next_medial)) = carry_over(ending) {
+                return Some(format!(
+                    "{}{}",
+                    syllable(initial, medial, final_consonant),
+                    syllable(next_initial, next_medial, 0)
+                ));
+            }
+        }
+        None
+    }
+}
+
+pub fn gen(keyboard: &mut KbdWriter) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Korean".to_string(),
+        "ko".to_string(),
+        "Hangul syllables composed from jamo on the two-set keyboard".to_string(),
+    ));
+
+    // modifier keys
+    keyboard.add_modifier(SYLLABLE_BREAK, "syllable break".to_string());
+
+    // jamo on their own
+    let mut jamo_map = KbdMap::new();
+    for (keys, jamo) in INITIALS.iter().chain(MEDIALS.iter()) {
+        jamo_map.add(keys.to_string(), jamo.to_string());
+    }
+    keyboard.write_section("jamo".to_string(), jamo_map);
+
+    // every syllable, composed when it is typed
+    keyboard.write_rule("syllables".to_string(), SyllableRule);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composition() {
+        assert_eq!(syllable(0, 0, 0), '가');
+        assert_eq!(syllable(18, 0, 4), '한');
+        assert_eq!(syllable(18, 20, 27), '힣');
+        assert_eq!(SyllableRule.apply("gks"), Some("한".to_string()));
+        assert_eq!(SyllableRule.apply("rmf"), Some("글".to_string()));
+        assert_eq!(SyllableRule.apply("dhkd"), Some("왕".to_string()));
+        assert_eq!(SyllableRule.apply("dlfr"), Some("읽".to_string()));
+        assert_eq!(SyllableRule.apply("rk'"), Some("가".to_string()));
+        assert_eq!(SyllableRule.apply("rkk"), None);
+        assert_eq!(SyllableRule.apply("kr"), None);
+    }
+
+    #[test]
+    fn carried_finals() {
+        assert_eq!(SyllableRule.apply("gksk"), Some("하나".to_string()));
+        assert_eq!(SyllableRule.apply("dlfrj"), Some("일거".to_string()));
+        assert_eq!(SyllableRule.apply("rhkfhk"), Some("과롸".to_string()));
+        assert_eq!(SyllableRule.apply("dlTj"), Some("이써".to_string()));
+        assert_eq!(SyllableRule.apply("gk'sk"), None);
+        assert_eq!(SyllableRule.apply("gksrk"), None);
+    }
+
+    #[test]
+    fn every_syllable() {
+        let rule = SyllableRule;
+        let mut syllables: Vec<String> = rule
+            .inputs()
+            .iter()
+            .filter(|seq| !seq.ends_with(SYLLABLE_BREAK))
+            .map(|seq| rule.apply(seq).unwrap())
+            .filter(|syllables| syllables.chars().count() == 1)
+            .collect();
+        syllables.sort();
+        syllables.dedup();
+        assert_eq!(syllables.len(), 11172);
+    }
+}
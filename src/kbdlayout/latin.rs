@@ -1,79 +1,347 @@
 use crate::{
-    compose::{compose_vec, CompositionMap},
+    compose::{compose_vec, Diacritic},
     kbdwriter::{KbdMap, KbdMetaData, KbdWriter},
 };
 
-pub fn gen(keyboard: &mut KbdWriter, postfix: bool) {
-    let compositions = CompositionMap::new();
-    let vowels: Vec<char> = vec!['a', 'e', 'i', 'o', 'u', 'y'];
+/// A diacritic typed with one key next to the letters it goes on
+pub struct Mark {
+    pub section: &'static str,
+    pub key: char,
+    pub diacritic: Diacritic,
+    /// The lowercase letters taking the diacritic
+    pub letters: Vec<char>,
+}
 
-    keyboard.set_metadata(KbdMetaData::new(
-        "Lingua Latina".to_string(),
-        "la".to_string(),
-        "Latin with macrons and breve".to_string(),
-    ));
-
-    let mut macron_map = KbdMap::new();
-    let macron_combine = ';';
-    for letter_lower in vowels.iter() {
-        let lowercase = match postfix {
-            true => vec![*letter_lower, macron_combine],
-            false => vec![macron_combine, *letter_lower],
+/// A Latin layout with diacritics typed after the letter, or before it if not postfix
+pub struct Latin {
+    pub metadata: KbdMetaData,
+    /// Letters beyond the Latin alphabet and the keys that type them
+    pub letters: Vec<(&'static str, char)>,
+    pub marks: Vec<Mark>,
+    /// Repeating the last key of a sequence types the sequence itself, `cxx` for `cx`
+    pub escape: bool,
+    pub postfix: bool,
+}
+
+/// The keys of a lowercase letter and of its capital, with the keys that follow the
+/// first one either in lowercase or in uppercase
+fn cases(letter: &str, mark: &str) -> Vec<(String, String, bool)> {
+    let mut chars = letter.chars();
+    let first: String = chars.next().unwrap().to_uppercase().collect();
+    let rest: String = chars.collect();
+    let mut sequences = vec![(letter.to_string(), mark.to_string(), false)];
+    for (rest, mark) in [
+        (rest.clone(), mark.to_string()),
+        (rest.to_uppercase(), mark.to_uppercase()),
+    ] {
+        let capital = (first.clone() + &rest, mark, true);
+        if !sequences.contains(&capital) {
+            sequences.push(capital);
         }
-        .iter()
-        .collect();
-        macron_map.add(
-            lowercase,
-            compose_vec(vec![*letter_lower, compositions.above("macron")])
-                .iter()
-                .collect(),
-        );
-        let u_str: Vec<char> = letter_lower.to_uppercase().collect();
-        let letter_upper = u_str[0];
-        let uppercase = match postfix {
-            true => vec![letter_upper, macron_combine],
-            false => vec![macron_combine, letter_upper],
+    }
+    sequences
+}
+
+impl Latin {
+    /// The keys that type a lowercase letter
+    fn keys(&self, letter: char) -> String {
+        match self.letters.iter().find(|(_, mapped)| *mapped == letter) {
+            Some((keys, _)) => keys.to_string(),
+            None => letter.to_string(),
         }
-        .iter()
-        .collect();
-        macron_map.add(
-            uppercase,
-            compose_vec(vec![letter_upper, compositions.above("macron")])
-                .iter()
-                .collect(),
-        );
     }
-    keyboard.write_section("macrons".to_string(), macron_map);
-
-    let mut breve_map = KbdMap::new();
-    let breve_combine = '-';
-    for letter_lower in vowels.iter() {
-        let lowercase = match postfix {
-            true => vec![*letter_lower, breve_combine],
-            false => vec![breve_combine, *letter_lower],
+
+    pub fn gen(self, keyboard: &mut KbdWriter) {
+        keyboard.set_metadata(self.metadata.clone());
+
+        // modifier keys, one key may type several diacritics
+        let mut modifiers: Vec<(char, Vec<&str>)> = Vec::new();
+        for mark in self.marks.iter() {
+            match modifiers.iter_mut().find(|(key, _)| *key == mark.key) {
+                Some((_, names)) => {
+                    if !names.contains(&mark.diacritic.name()) {
+                        names.push(mark.diacritic.name());
+                    }
+                }
+                None => modifiers.push((mark.key, vec![mark.diacritic.name()])),
+            }
+        }
+        for (key, names) in modifiers {
+            keyboard.add_modifier(key, names.join(", "));
         }
-        .iter()
-        .collect();
-        breve_map.add(
-            lowercase,
-            compose_vec(vec![*letter_lower, compositions.above("breve")])
-                .iter()
-                .collect(),
-        );
-        let u_str: Vec<char> = letter_lower.to_uppercase().collect();
-        let letter_upper = u_str[0];
-        let uppercase = match postfix {
-            true => vec![letter_upper, breve_combine],
-            false => vec![breve_combine, letter_upper],
+
+        let mut escape_map = KbdMap::new();
+        let mut add_escape = |seq: &String| {
+            let mut escaped = seq.clone();
+            escaped.push(seq.chars().last().unwrap());
+            escape_map.add(escaped, seq.clone());
+        };
+
+        // letters
+        if !self.letters.is_empty() {
+            let mut letter_map = KbdMap::new();
+            for (keys, letter) in self.letters.iter() {
+                for (seq, _, capital) in cases(keys, "") {
+                    letter_map.add(
+                        seq.clone(),
+                        match capital {
+                            true => letter.to_uppercase().to_string(),
+                            false => letter.to_string(),
+                        },
+                    );
+                    if self.escape {
+                        add_escape(&seq);
+                    }
+                }
+            }
+            keyboard.write_section("letters".to_string(), letter_map);
+        }
+
+        // diacritics, the marks of one section in a single map
+        let mut sections: Vec<(&str, KbdMap)> = Vec::new();
+        for mark in self.marks.iter() {
+            let index = match sections.iter().position(|(name, _)| *name == mark.section) {
+                Some(index) => index,
+                None => {
+                    sections.push((mark.section, KbdMap::new()));
+                    sections.len() - 1
+                }
+            };
+            let (_, map) = &mut sections[index];
+            for letter in mark.letters.iter() {
+                for (keys, mark_keys, capital) in cases(&self.keys(*letter), &mark.key.to_string())
+                {
+                    let seq = match self.postfix {
+                        true => keys + &mark_keys,
+                        false => mark_keys + &keys,
+                    };
+                    let base = match capital {
+                        true => letter.to_uppercase().next().unwrap(),
+                        false => *letter,
+                    };
+                    map.add(
+                        seq.clone(),
+                        compose_vec(vec![base, mark.diacritic.char()])
+                            .iter()
+                            .collect(),
+                    );
+                    if self.escape {
+                        add_escape(&seq);
+                    }
+                }
+            }
+        }
+        for (name, map) in sections {
+            keyboard.write_section(name.to_string(), map);
+        }
+
+        if self.escape {
+            keyboard.write_section("escapes".to_string(), escape_map);
+        }
+    }
+}
+
+pub fn gen(keyboard: &mut KbdWriter, postfix: bool) {
+    let vowels: Vec<char> = vec!['a', 'e', 'i', 'o', 'u', 'y'];
+
+    Latin {
+        metadata: KbdMetaData::new(
+            "Lingua Latina".to_string(),
+            "la".to_string(),
+            "Latin with macrons and breve".to_string(),
+        ),
+        letters: vec![],
+        marks: vec![
+            Mark {
+                section: "macrons",
+                key: ';',
+                diacritic: Diacritic::Macron,
+                letters: vowels.clone(),
+            },
+            Mark {
+                section: "breve",
+                key: '-',
+                diacritic: Diacritic::Breve,
+                letters: vowels,
+            },
+        ],
+        escape: false,
+        postfix,
+    }
+    .gen(keyboard);
+}
+
+pub fn gen_esperanto(keyboard: &mut KbdWriter) {
+    let circumflex: Vec<char> = vec!['c', 'g', 'h', 'j', 's'];
+
+    Latin {
+        metadata: KbdMetaData::new(
+            "Esperanto".to_string(),
+            "eo".to_string(),
+            "Esperanto typed with the x-system or the h-system".to_string(),
+        ),
+        letters: vec![],
+        marks: vec![
+            Mark {
+                section: "x-system",
+                key: 'x',
+                diacritic: Diacritic::Circumflex,
+                letters: circumflex.clone(),
+            },
+            Mark {
+                section: "x-system",
+                key: 'x',
+                diacritic: Diacritic::Breve,
+                letters: vec!['u'],
+            },
+            // the h-system writes ŭ as a plain u
+            Mark {
+                section: "h-system",
+                key: 'h',
+                diacritic: Diacritic::Circumflex,
+                letters: circumflex,
+            },
+        ],
+        escape: true,
+        postfix: true,
+    }
+    .gen(keyboard);
+}
+
+pub fn gen_old_norse(keyboard: &mut KbdWriter) {
+    Latin {
+        metadata: KbdMetaData::new(
+            "Old Norse".to_string(),
+            "non".to_string(),
+            "Normalized Old Norse with acute and ogonek".to_string(),
+        ),
+        letters: vec![
+            ("th", 'þ'),
+            ("dh", 'ð'),
+            ("ae", 'æ'),
+            ("oe", 'œ'),
+            ("o/", 'ø'),
+        ],
+        marks: vec![
+            Mark {
+                section: "acute",
+                key: '\'',
+                diacritic: Diacritic::Acute,
+                letters: vec!['a', 'e', 'i', 'o', 'u', 'y', 'æ', 'ø'],
+            },
+            Mark {
+                section: "ogonek",
+                key: ',',
+                diacritic: Diacritic::Ogonek,
+                letters: vec!['o'],
+            },
+        ],
+        escape: true,
+        postfix: true,
+    }
+    .gen(keyboard);
+}
+
+pub fn gen_old_english(keyboard: &mut KbdWriter) {
+    Latin {
+        metadata: KbdMetaData::new(
+            "Old English".to_string(),
+            "ang".to_string(),
+            "Old English with macrons and dotted consonants".to_string(),
+        ),
+        letters: vec![
+            ("th", 'þ'),
+            ("dh", 'ð'),
+            ("ae", 'æ'),
+            ("wy", 'ƿ'),
+            ("gh", 'ȝ'),
+        ],
+        marks: vec![
+            Mark {
+                section: "macrons",
+                key: ';',
+                diacritic: Diacritic::Macron,
+                letters: vec!['a', 'e', 'i', 'o', 'u', 'y', 'æ'],
+            },
+            Mark {
+                section: "dot above",
+                key: '\'',
+                diacritic: Diacritic::DotAbove,
+                letters: vec!['c', 'g'],
+            },
+        ],
+        escape: true,
+        postfix: true,
+    }
+    .gen(keyboard);
+}
+
+/// Lithuanian does not use q, w and x, they type the diacritics
+pub fn gen_lithuanian(keyboard: &mut KbdWriter) {
+    Latin {
+        metadata: KbdMetaData::new(
+            "Lithuanian".to_string(),
+            "lt".to_string(),
+            "Lithuanian with diacritics typed after the letter".to_string(),
+        ),
+        letters: vec![],
+        marks: vec![
+            Mark {
+                section: "ogonek",
+                key: 'q',
+                diacritic: Diacritic::Ogonek,
+                letters: vec!['a', 'e', 'i', 'u'],
+            },
+            Mark {
+                section: "caron",
+                key: 'x',
+                diacritic: Diacritic::Caron,
+                letters: vec!['c', 's', 'z'],
+            },
+            Mark {
+                section: "dot above and macron",
+                key: 'w',
+                diacritic: Diacritic::DotAbove,
+                letters: vec!['e'],
+            },
+            Mark {
+                section: "dot above and macron",
+                key: 'w',
+                diacritic: Diacritic::Macron,
+                letters: vec!['u'],
+            },
+        ],
+        escape: true,
+        postfix: true,
+    }
+    .gen(keyboard);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape() {
+        let mut kbd = KbdWriter::new();
+        gen_esperanto(&mut kbd);
+        let mapped: Vec<(&String, &String)> = kbd
+            .sections
+            .iter()
+            .flat_map(|(_, map)| map.iter())
+            .collect();
+        for (seq, output) in [
+            ("cx", "ĉ"),
+            ("Cx", "Ĉ"),
+            ("CX", "Ĉ"),
+            ("ux", "ŭ"),
+            ("gh", "ĝ"),
+            ("cxx", "cx"),
+            ("CXX", "CX"),
+            ("ghh", "gh"),
+        ] {
+            assert!(mapped.contains(&(&seq.to_string(), &output.to_string())));
         }
-        .iter()
-        .collect();
-        breve_map.add(
-            uppercase,
-            compose_vec(vec![letter_upper, compositions.above("breve")])
-                .iter()
-                .collect(),
-        );
+        assert_eq!(kbd.modifiers.get(&'x').unwrap(), "circumflex, breve");
     }
-    keyboard.write_section("breve".to_string(), breve_map);
 }
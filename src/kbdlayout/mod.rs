@@ -1,11 +1,21 @@
 use std::fmt;
 
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use unicode_script::Script;
 use variant_count::VariantCount;
 
+use crate::kbdwriter::KbdWriter;
+
+pub mod arabic;
+pub mod cyrillic;
 pub mod greek;
+pub mod hebrew;
+pub mod ipa;
+pub mod korean;
 pub mod latin;
-pub mod russian;
+pub mod pinyin;
+pub mod sanskrit;
+pub mod vietnamese;
 
 #[derive(
     clap::ArgEnum,
@@ -23,6 +33,26 @@ pub enum Layout {
     Greek,
     Latin,
     Russian,
+    SanskritIast,
+    SanskritDevanagari,
+    Hebrew,
+    HebrewCantillation,
+    Arabic,
+    VietnameseTelex,
+    VietnameseVni,
+    Pinyin,
+    Ipa,
+    GreekMonotonic,
+    Ukrainian,
+    Belarusian,
+    Serbian,
+    Bulgarian,
+    ChurchSlavonic,
+    Esperanto,
+    OldNorse,
+    OldEnglish,
+    Lithuanian,
+    Korean,
 }
 
 impl fmt::Display for Layout {
@@ -30,3 +60,43 @@ impl fmt::Display for Layout {
         write!(f, "{:?}", self)
     }
 }
+
+impl Layout {
+    /// Writes every section of the layout
+    pub fn gen(&self, kbd: &mut KbdWriter) {
+        match self {
+            Layout::Greek => greek::gen(kbd),
+            Layout::Latin => latin::gen(kbd, true),
+            Layout::Russian => cyrillic::gen_russian(kbd),
+            Layout::SanskritIast => sanskrit::gen_iast(kbd),
+            Layout::SanskritDevanagari => sanskrit::gen_devanagari(kbd),
+            Layout::Hebrew => hebrew::gen(kbd, false),
+            Layout::HebrewCantillation => hebrew::gen(kbd, true),
+            Layout::Arabic => arabic::gen(kbd),
+            Layout::VietnameseTelex => vietnamese::gen(kbd, vietnamese::Method::Telex),
+            Layout::VietnameseVni => vietnamese::gen(kbd, vietnamese::Method::Vni),
+            Layout::Pinyin => pinyin::gen(kbd),
+            Layout::Ipa => ipa::gen(kbd),
+            Layout::GreekMonotonic => greek::gen_monotonic(kbd),
+            Layout::Ukrainian => cyrillic::gen_ukrainian(kbd),
+            Layout::Belarusian => cyrillic::gen_belarusian(kbd),
+            Layout::Serbian => cyrillic::gen_serbian(kbd),
+            Layout::Bulgarian => cyrillic::gen_bulgarian(kbd),
+            Layout::ChurchSlavonic => cyrillic::gen_church_slavonic(kbd),
+            Layout::Esperanto => latin::gen_esperanto(kbd),
+            Layout::OldNorse => latin::gen_old_norse(kbd),
+            Layout::OldEnglish => latin::gen_old_english(kbd),
+            Layout::Lithuanian => latin::gen_lithuanian(kbd),
+            Layout::Korean => korean::gen(kbd),
+        }
+    }
+
+    /// Scripts whose letters the layout uses on purpose besides its main script
+    pub fn extra_scripts(&self) -> &'static [Script] {
+        match self {
+            // β, θ and χ are IPA letters borrowed from Greek
+            Layout::Ipa => &[Script::Greek],
+            _ => &[],
+        }
+    }
+}
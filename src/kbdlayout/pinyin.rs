@@ -0,0 +1,172 @@
+use crate::{
+    compose::{compose_vec, Diacritic},
+    kbdwriter::{KbdMap, KbdMetaData, KbdRule, KbdWriter},
+};
+
+/// Every syllable of Standard Mandarin, ü is typed as v
+const SYLLABLES: &str = "\
+a ai an ang ao e ei en eng er o ou \
+yi ya yao ye you yan yin yang ying yong yu yue yuan yun \
+wu wa wo wai wei wan wen wang weng \
+ba bo bai bei bao ban ben bang beng bi biao bie bian bin bing bu \
+pa po pai pei pao pou pan pen pang peng pi piao pie pian pin ping pu \
+ma mo me mai mei mao mou man men mang meng mi miao mie miu mian min ming mu \
+fa fo fei fou fan fen fang feng fu \
+da de dai dei dao dou dan den dang deng dong di dia diao die diu dian ding du duo dui duan dun \
+ta te tai tei tao tou tan tang teng tong ti tiao tie tian ting tu tuo tui tuan tun \
+na ne nai nei nao nou nan nen nang neng nong ni niao nie niu nian nin niang ning \
+nu nuo nuan nv nve \
+la le lai lei lao lou lan lang leng long li lia liao lie liu lian lin liang ling \
+lu luo luan lun lv lve \
+ga ge gai gei gao gou gan gen gang geng gong gu gua guo guai gui guan gun guang \
+ka ke kai kei kao kou kan ken kang keng kong ku kua kuo kuai kui kuan kun kuang \
+ha he hai hei hao hou han hen hang heng hong hu hua huo huai hui huan hun huang \
+ji jia jiao jie jiu jian jin jiang jing jiong ju jue juan jun \
+qi qia qiao qie qiu qian qin qiang qing qiong qu que quan qun \
+xi xia xiao xie xiu xian xin xiang xing xiong xu xue xuan xun \
+zha zhe zhi zhai zhei zhao zhou zhan zhen zhang zheng zhong \
+zhu zhua zhuo zhuai zhui zhuan zhun zhuang \
+cha che chi chai chao chou chan chen chang cheng chong \
+chu chua chuo chuai chui chuan chun chuang \
+sha she shi shai shei shao shou shan shen shang sheng \
+shu shua shuo shuai shui shuan shun shuang \
+re ri rao rou ran ren rang reng rong ru rua ruo rui ruan run \
+za ze zi zai zei zao zou zan zen zang zeng zong zu zuo zui zuan zun \
+ca ce ci cai cao cou can cen cang ceng cong cu cuo cui cuan cun \
+sa se si sai sao sou san sen sang seng song su suo sui suan sun";
+
+const VOWELS: &str = "aeiouüAEIOUÜ";
+
+/// Marks one tone on a syllable: on a or e if there is one, on the o of ou,
+/// otherwise on the last vowel
+pub fn place_tone(syllable: &str, tone: Diacritic) -> String {
+    let letters: Vec<char> = syllable.chars().collect();
+    let lower = syllable.to_lowercase();
+    let position = match (lower.find(['a', 'e']), lower.find("ou")) {
+        (Some(i), _) | (None, Some(i)) => lower[..i].chars().count(),
+        (None, None) => match letters.iter().rposition(|c| VOWELS.contains(*c)) {
+            Some(i) => i,
+            None => return syllable.to_string(),
+        },
+    };
+    let mut output: Vec<char> = letters[..=position].to_vec();
+    output.push(tone.char());
+    output.extend(&letters[position + 1..]);
+    compose_vec(output).iter().collect()
+}
+
+fn capitalize(syllable: &str) -> String {
+    let mut chars = syllable.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// A syllable followed by a tone digit, 5 for the neutral tone
+struct ToneRule {
+    tone_key: char,
+    tone: Option<Diacritic>,
+}
+
+impl KbdRule for ToneRule {
+    fn inputs(&self) -> Vec<String> {
+        let mut inputs = Vec::new();
+        for syllable in SYLLABLES.split(' ') {
+            for seq in [syllable.to_string(), capitalize(syllable)] {
+                inputs.push(format!("{}{}", seq, self.tone_key));
+            }
+        }
+        inputs
+    }
+
+    fn apply(&self, seq: &str) -> Option<String> {
+        let syllable = seq.strip_suffix(self.tone_key)?;
+        // only the syllables, lowercase or capitalized, that `inputs` lists
+        let lower = syllable.to_lowercase();
+        if !SYLLABLES.split(' ').any(|s| s == lower)
+            || (syllable != lower && syllable != capitalize(&lower))
+        {
+            return None;
+        }
+        let syllable = syllable.replace('v', "ü").replace('V', "Ü");
+        match self.tone {
+            Some(tone) => Some(place_tone(&syllable, tone)),
+            None => Some(syllable),
+        }
+    }
+}
+
+pub fn gen(keyboard: &mut KbdWriter) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Hanyu Pinyin".to_string(),
+        "zh-Latn".to_string(),
+        "Pinyin syllables with the tone digit typed last".to_string(),
+    ));
+
+    let tones: Vec<(char, Option<Diacritic>, &str)> = vec![
+        ('1', Some(Diacritic::Macron), "first tone"),
+        ('2', Some(Diacritic::Acute), "second tone"),
+        ('3', Some(Diacritic::Caron), "third tone"),
+        ('4', Some(Diacritic::Grave), "fourth tone"),
+        ('5', None, "neutral tone"),
+    ];
+
+    // modifier keys
+    for (key, tone, name) in tones.iter() {
+        keyboard.add_modifier(
+            *key,
+            match tone {
+                Some(tone) => tone.name().to_string(),
+                None => name.to_string(),
+            },
+        );
+    }
+    keyboard.add_modifier('v', "ü".to_string());
+
+    // ü without a tone
+    let mut umlaut_map = KbdMap::new();
+    for syllable in SYLLABLES.split(' ').filter(|s| s.contains('v')) {
+        let capital = capitalize(syllable);
+        umlaut_map.add(syllable.to_string(), syllable.replace('v', "ü"));
+        umlaut_map.add(capital.clone(), capital.replace('v', "ü"));
+    }
+    umlaut_map.add("v".to_string(), "ü".to_string());
+    umlaut_map.add("V".to_string(), "Ü".to_string());
+    keyboard.write_section("ü".to_string(), umlaut_map);
+
+    // one section per tone
+    for (tone_key, tone, name) in tones {
+        keyboard.write_rule(name.to_string(), ToneRule { tone_key, tone });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tone_placement() {
+        assert_eq!(place_tone("zhong", Diacritic::Macron), "zhōng");
+        assert_eq!(place_tone("hao", Diacritic::Caron), "hǎo");
+        assert_eq!(place_tone("xue", Diacritic::Acute), "xué");
+        assert_eq!(place_tone("gou", Diacritic::Caron), "gǒu");
+        assert_eq!(place_tone("liu", Diacritic::Acute), "liú");
+        assert_eq!(place_tone("gui", Diacritic::Grave), "guì");
+        assert_eq!(place_tone("lü", Diacritic::Grave), "lǜ");
+        assert_eq!(place_tone("Ai", Diacritic::Grave), "Ài");
+    }
+    #[test]
+    fn rule() {
+        let rule = ToneRule {
+            tone_key: '3',
+            tone: Some(Diacritic::Caron),
+        };
+        assert_eq!(rule.apply("nv3"), Some("nǚ".to_string()));
+        assert_eq!(rule.apply("Lve3"), Some("Lüě".to_string()));
+        assert_eq!(rule.apply("ma4"), None);
+        assert_eq!(rule.apply("hello3"), None);
+        assert_eq!(rule.apply("ZHONG3"), None);
+        assert!(rule.inputs().contains(&"Zhong3".to_string()));
+    }
+}
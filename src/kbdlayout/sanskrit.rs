@@ -0,0 +1,274 @@
+use crate::{
+    compose::{compose_vec, Diacritic},
+    kbdwriter::{KbdMap, KbdMetaData, KbdWriter},
+};
+
+/// Adds a lowercase and a capital mapping of a letter with diacritics, typed postfix
+fn add_both(map: &mut KbdMap, letter: char, keys: &str, diacritics: &[Diacritic]) {
+    for letter in [letter, letter.to_uppercase().next().unwrap()] {
+        let mut seq = letter.to_string();
+        seq.push_str(keys);
+        let mut output = vec![letter];
+        output.extend(diacritics.iter().map(|d| d.char()));
+        map.add(seq, compose_vec(output).iter().collect());
+    }
+}
+
+pub fn gen_iast(keyboard: &mut KbdWriter) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Sanskrit (IAST)".to_string(),
+        "sa-Latn".to_string(),
+        "Sanskrit in the International Alphabet of Sanskrit Transliteration".to_string(),
+    ));
+
+    // modifier keys
+    let modifier_macron: char = ';';
+    let modifier_dot_below: char = '.';
+    let modifier_dot_above: char = ',';
+    let modifier_acute: char = '/';
+    let modifier_tilde: char = '~';
+    keyboard.add_modifier(modifier_macron, Diacritic::Macron.name().to_string());
+    keyboard.add_modifier(modifier_dot_below, Diacritic::DotBelow.name().to_string());
+    keyboard.add_modifier(modifier_dot_above, Diacritic::DotAbove.name().to_string());
+    keyboard.add_modifier(modifier_acute, Diacritic::Acute.name().to_string());
+    keyboard.add_modifier(modifier_tilde, Diacritic::Tilde.name().to_string());
+
+    let long_vowels: Vec<char> = vec!['a', 'i', 'u'];
+    let vocalic: Vec<char> = vec!['r', 'l'];
+    let retroflex: Vec<char> = vec!['t', 'd', 'n', 's'];
+
+    // long vowels
+    let mut vowel_map = KbdMap::new();
+    for vowel in long_vowels.iter() {
+        add_both(
+            &mut vowel_map,
+            *vowel,
+            &modifier_macron.to_string(),
+            &[Diacritic::Macron],
+        );
+    }
+    keyboard.write_section("long vowels".to_string(), vowel_map);
+
+    // vocalic r and l, short and long
+    let mut vocalic_map = KbdMap::new();
+    for letter in vocalic.iter() {
+        add_both(
+            &mut vocalic_map,
+            *letter,
+            &modifier_dot_below.to_string(),
+            &[Diacritic::DotBelow],
+        );
+        add_both(
+            &mut vocalic_map,
+            *letter,
+            &[modifier_dot_below, modifier_macron]
+                .iter()
+                .collect::<String>(),
+            &[Diacritic::DotBelow, Diacritic::Macron],
+        );
+    }
+    keyboard.write_section("vocalic liquids".to_string(), vocalic_map);
+
+    // consonants
+    let mut consonant_map = KbdMap::new();
+    for letter in retroflex.iter() {
+        add_both(
+            &mut consonant_map,
+            *letter,
+            &modifier_dot_below.to_string(),
+            &[Diacritic::DotBelow],
+        );
+    }
+    add_both(
+        &mut consonant_map,
+        'n',
+        &modifier_dot_above.to_string(),
+        &[Diacritic::DotAbove],
+    );
+    add_both(
+        &mut consonant_map,
+        'n',
+        &modifier_tilde.to_string(),
+        &[Diacritic::Tilde],
+    );
+    add_both(
+        &mut consonant_map,
+        's',
+        &modifier_acute.to_string(),
+        &[Diacritic::Acute],
+    );
+    keyboard.write_section("consonants".to_string(), consonant_map);
+
+    // anusvara and visarga
+    let mut sign_map = KbdMap::new();
+    add_both(
+        &mut sign_map,
+        'm',
+        &modifier_dot_below.to_string(),
+        &[Diacritic::DotBelow],
+    );
+    add_both(
+        &mut sign_map,
+        'm',
+        &modifier_dot_above.to_string(),
+        &[Diacritic::DotAbove],
+    );
+    add_both(
+        &mut sign_map,
+        'h',
+        &modifier_dot_below.to_string(),
+        &[Diacritic::DotBelow],
+    );
+    keyboard.write_section("anusvara and visarga".to_string(), sign_map);
+}
+
+pub fn gen_devanagari(keyboard: &mut KbdWriter) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Sanskrit (Devanagari)".to_string(),
+        "sa".to_string(),
+        "Phonetic Devanagari typed in Harvard-Kyoto".to_string(),
+    ));
+
+    let virama: char = '\u{94d}';
+
+    // Harvard-Kyoto keys
+    let consonants: Vec<(&str, char)> = vec![
+        ("k", 'क'),
+        ("kh", 'ख'),
+        ("g", 'ग'),
+        ("gh", 'घ'),
+        ("G", 'ङ'),
+        ("c", 'च'),
+        ("ch", 'छ'),
+        ("j", 'ज'),
+        ("jh", 'झ'),
+        ("J", 'ञ'),
+        ("T", 'ट'),
+        ("Th", 'ठ'),
+        ("D", 'ड'),
+        ("Dh", 'ढ'),
+        ("N", 'ण'),
+        ("t", 'त'),
+        ("th", 'थ'),
+        ("d", 'द'),
+        ("dh", 'ध'),
+        ("n", 'न'),
+        ("p", 'प'),
+        ("ph", 'फ'),
+        ("b", 'ब'),
+        ("bh", 'भ'),
+        ("m", 'म'),
+        ("y", 'य'),
+        ("r", 'र'),
+        ("l", 'ल'),
+        ("v", 'व'),
+        ("z", 'श'),
+        ("S", 'ष'),
+        ("s", 'स'),
+        ("h", 'ह'),
+    ];
+    // independent vowel and the vowel sign written after a consonant
+    let vowels: Vec<(&str, char, Option<char>)> = vec![
+        ("a", 'अ', None),
+        ("A", 'आ', Some('ा')),
+        ("i", 'इ', Some('ि')),
+        ("I", 'ई', Some('ी')),
+        ("u", 'उ', Some('ु')),
+        ("U", 'ऊ', Some('ू')),
+        ("R", 'ऋ', Some('ृ')),
+        ("RR", 'ॠ', Some('ॄ')),
+        ("lR", 'ऌ', Some('ॢ')),
+        ("lRR", 'ॡ', Some('ॣ')),
+        ("e", 'ए', Some('े')),
+        ("ai", 'ऐ', Some('ै')),
+        ("o", 'ओ', Some('ो')),
+        ("au", 'औ', Some('ौ')),
+    ];
+    let signs: Vec<(&str, char)> = vec![
+        ("M", 'ं'),
+        ("H", 'ः'),
+        ("~", 'ँ'),
+        ("'", 'ऽ'),
+        ("|", '।'),
+        ("||", '॥'),
+        ("OM", 'ॐ'),
+    ];
+    let digits = "०१२३४५६७८९";
+
+    // independent vowels
+    let mut vowel_map = KbdMap::new();
+    for (seq, vowel, _) in vowels.iter() {
+        vowel_map.add(seq.to_string(), vowel.to_string());
+    }
+    keyboard.write_section("vowels".to_string(), vowel_map);
+
+    // bare consonants take a virama, so typing consonants in a row builds a conjunct
+    let mut consonant_map = KbdMap::new();
+    for (seq, consonant) in consonants.iter() {
+        consonant_map.add(seq.to_string(), [*consonant, virama].iter().collect());
+    }
+    keyboard.write_section("consonants".to_string(), consonant_map);
+
+    // consonant-vowel matrix
+    let mut syllable_map = KbdMap::new();
+    for (seq, consonant) in consonants.iter() {
+        for (vowel_seq, _, sign) in vowels.iter() {
+            let mut syllable_seq = seq.to_string();
+            syllable_seq.push_str(vowel_seq);
+            // 'l' followed by 'R' types the vocalic l instead
+            if vowels.iter().any(|(v, _, _)| *v == syllable_seq) {
+                continue;
+            }
+            let mut syllable = consonant.to_string();
+            if let Some(sign) = sign {
+                syllable.push(*sign);
+            }
+            syllable_map.add(syllable_seq, syllable);
+        }
+    }
+    keyboard.write_section("syllables".to_string(), syllable_map);
+
+    // signs and punctuation
+    let mut sign_map = KbdMap::new();
+    for (seq, sign) in signs.iter() {
+        sign_map.add(seq.to_string(), sign.to_string());
+    }
+    keyboard.write_section("signs".to_string(), sign_map);
+
+    // digits
+    let mut digit_map = KbdMap::new();
+    for (key, digit) in ('0'..='9').zip(digits.chars()) {
+        digit_map.add(key.to_string(), digit.to_string());
+    }
+    keyboard.write_section("digits".to_string(), digit_map);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devanagari() {
+        let mut kbd = KbdWriter::new();
+        gen_devanagari(&mut kbd);
+        // a bare consonant keeps its virama, so the next one joins it in a conjunct
+        let conjunct = [kbd.lookup("k").unwrap(), kbd.lookup("ta").unwrap()].concat();
+        assert_eq!(conjunct, "क्त");
+        // the vowel sign is typed after the consonant even when drawn before it
+        assert_eq!(kbd.lookup("ki"), Some("कि"));
+        assert_eq!(kbd.lookup("ka"), Some("क"));
+        // lR is the vocalic l, not l followed by R
+        assert_eq!(kbd.lookup("klR"), Some("कॢ"));
+        assert_eq!(kbd.lookup("lR"), Some("ऌ"));
+    }
+
+    #[test]
+    fn iast() {
+        let mut kbd = KbdWriter::new();
+        gen_iast(&mut kbd);
+        assert_eq!(kbd.lookup("r."), Some("ṛ"));
+        assert_eq!(kbd.lookup("r.;"), Some("ṝ"));
+        assert_eq!(kbd.lookup("R.;"), Some("Ṝ"));
+        assert_eq!(kbd.lookup("a;"), Some("ā"));
+    }
+}
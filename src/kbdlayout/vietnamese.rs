@@ -0,0 +1,405 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    compose::{compose_vec, Diacritic},
+    kbdwriter::{KbdMap, KbdMetaData, KbdRule, KbdWriter},
+};
+
+/// How vowel marks and tones are typed
+#[derive(Clone, Copy, PartialEq)]
+pub enum Method {
+    /// Letters, `aa` for â, `w` for breve and horn, `sfrxj` for the tones
+    Telex,
+    /// Digits, `6` `7` `8` for circumflex, horn and breve, `1` to `5` for the tones
+    Vni,
+}
+
+impl Method {
+    /// Keys typed after a vowel to add a vowel mark
+    fn mark_keys(self, vowel: char, mark: Diacritic) -> String {
+        match (self, mark) {
+            (Method::Telex, Diacritic::Circumflex) => vowel.to_string(),
+            (Method::Telex, _) => "w".to_string(),
+            (Method::Vni, Diacritic::Circumflex) => "6".to_string(),
+            (Method::Vni, Diacritic::Horn) => "7".to_string(),
+            (Method::Vni, _) => "8".to_string(),
+        }
+    }
+
+    /// Key typed after d for đ
+    fn stroke_key(self) -> char {
+        match self {
+            Method::Telex => 'd',
+            Method::Vni => '9',
+        }
+    }
+
+    fn tone_keys(self) -> [char; 5] {
+        match self {
+            Method::Telex => ['s', 'f', 'r', 'x', 'j'],
+            Method::Vni => ['1', '2', '3', '4', '5'],
+        }
+    }
+
+    /// The key sequences typing a vowel letter, Telex also types ư with w alone
+    fn letter_keys(self, letter: char) -> Vec<String> {
+        match MARKED_VOWELS
+            .iter()
+            .find(|(marked, _, _)| *marked == letter)
+        {
+            Some((_, vowel, mark)) => {
+                let mut keys = vec![format!("{}{}", vowel, self.mark_keys(*vowel, *mark))];
+                if self == Method::Telex && letter == 'ư' {
+                    keys.push("w".to_string());
+                }
+                keys
+            }
+            None => vec![letter.to_string()],
+        }
+    }
+
+    /// The key sequences typing a vowel cluster, ươ also with the horn key typed once
+    fn nucleus_keys(self, nucleus: &str) -> Vec<String> {
+        let letters: Vec<char> = nucleus.chars().collect();
+        let mut sequences = vec![String::new()];
+        let mut i = 0;
+        while i < letters.len() {
+            let keys = match letters[i..].starts_with(&['ư', 'ơ']) {
+                true => {
+                    i += 2;
+                    let mut keys: Vec<String> = self
+                        .letter_keys('ư')
+                        .iter()
+                        .flat_map(|u| {
+                            self.letter_keys('ơ')
+                                .into_iter()
+                                .map(move |o| u.clone() + &o)
+                        })
+                        .collect();
+                    keys.push(format!("uo{}", self.mark_keys('o', Diacritic::Horn)));
+                    keys
+                }
+                false => {
+                    i += 1;
+                    self.letter_keys(letters[i - 1])
+                }
+            };
+            sequences = sequences
+                .iter()
+                .flat_map(|seq| keys.iter().map(move |key| seq.clone() + key))
+                .collect();
+        }
+        sequences
+    }
+}
+
+/// The vowels with a circumflex, breve or horn
+const MARKED_VOWELS: [(char, char, Diacritic); 6] = [
+    ('â', 'a', Diacritic::Circumflex),
+    ('ă', 'a', Diacritic::Breve),
+    ('ê', 'e', Diacritic::Circumflex),
+    ('ô', 'o', Diacritic::Circumflex),
+    ('ơ', 'o', Diacritic::Horn),
+    ('ư', 'u', Diacritic::Horn),
+];
+
+/// The vowel clusters of a syllable, uơ of thuở is typed like ươ and left out
+const NUCLEI: [&str; 54] = [
+    "a", "ă", "â", "e", "ê", "i", "o", "ô", "ơ", "u", "ư", "y", //
+    "ai", "ao", "au", "âu", "ay", "ây", "eo", "êu", "ia", "iê", "iu", "oa", "oă", "oe", "oi", "ôi",
+    "ơi", "oo", "ua", "uâ", "uê", "ui", "uô", "ưa", "ưi", "ưu", "ươ", "uy", "yê", "iêu", "oai",
+    "oao", "oay", "oeo", "uây", "uôi", "ươi", "ươu", "uya", "uyê", "uyu", "yêu",
+];
+
+/// The consonants ending a syllable, longest first
+const FINALS: [&str; 8] = ["ng", "nh", "ch", "c", "m", "n", "p", "t"];
+
+/// The consonants starting a syllable, longest first, đ is typed with the stroke key
+const INITIALS: [&str; 26] = [
+    "ngh", "ng", "nh", "ch", "gh", "gi", "kh", "ph", "qu", "th", "tr", "b", "c", "d", "g", "h",
+    "k", "l", "m", "n", "p", "r", "s", "t", "v", "x",
+];
+
+/// The initials whose vowel letter is part of the consonant, so that the tone goes elsewhere
+const GLIDE_INITIALS: [&str; 2] = ["gi", "qu"];
+
+/// The index of the vowel taking the tone: the last vowel with a mark, the last vowel before
+/// a final consonant, the second of oa, oe and uy, and otherwise the middle or first vowel
+fn tone_position(nucleus: &[char], closed: bool) -> usize {
+    if let Some(i) = nucleus
+        .iter()
+        .rposition(|c| MARKED_VOWELS.iter().any(|(marked, _, _)| marked == c))
+    {
+        return i;
+    }
+    let lower: String = nucleus.iter().collect();
+    match nucleus.len() {
+        1 => 0,
+        _ if closed => nucleus.len() - 1,
+        2 if ["oa", "oe", "uy"].contains(&lower.as_str()) => 1,
+        2 => 0,
+        _ => 1,
+    }
+}
+
+fn capitalize(syllable: &str) -> String {
+    let mut chars = syllable.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The vowel marks and the tone of a syllable, the tone key typed last. The initial consonant
+/// types itself, so only the initials moving the tone are listed with the rimes
+struct SyllableRule {
+    method: Method,
+    tones: [Diacritic; 5],
+    /// The vowel cluster typed by each key sequence
+    nuclei: BTreeMap<String, &'static str>,
+}
+
+impl SyllableRule {
+    fn new(method: Method, tones: [Diacritic; 5]) -> Self {
+        let mut nuclei = BTreeMap::new();
+        for nucleus in NUCLEI.iter() {
+            for keys in method.nucleus_keys(nucleus) {
+                nuclei.entry(keys).or_insert(*nucleus);
+            }
+        }
+        Self {
+            method,
+            tones,
+            nuclei,
+        }
+    }
+
+    /// The lowercase syllable, without checking the case of the keys
+    fn syllable(&self, seq: &str) -> Option<String> {
+        let method = self.method;
+        let (rest, tone) = match method
+            .tone_keys()
+            .iter()
+            .position(|key| seq.ends_with(*key))
+        {
+            Some(i) => (&seq[..seq.len() - 1], Some(self.tones[i])),
+            None => (seq, None),
+        };
+        let stroke: String = ['d', method.stroke_key()].iter().collect();
+        let (initial, rest) = match rest.strip_prefix(&stroke) {
+            Some(rest) => ("đ", rest),
+            None => match INITIALS.iter().find(|initial| rest.starts_with(*initial)) {
+                Some(initial) => (*initial, &rest[initial.len()..]),
+                None => ("", rest),
+            },
+        };
+        let (keys, final_consonant) = match FINALS.iter().find(|f| rest.ends_with(*f)) {
+            Some(f) => (&rest[..rest.len() - f.len()], *f),
+            None => (rest, ""),
+        };
+        // gi is also the vowel i after g
+        let (initial, nucleus): (&str, Vec<char>) = match (initial, keys) {
+            ("gi", "") => ("g", vec!['i']),
+            _ => (initial, self.nuclei.get(keys)?.chars().collect()),
+        };
+        if GLIDE_INITIALS.contains(&initial) && nucleus[0] == initial.chars().nth(1).unwrap() {
+            return None;
+        }
+        let mut letters: Vec<char> = initial.chars().collect();
+        let position = tone_position(&nucleus, !final_consonant.is_empty());
+        for (i, vowel) in nucleus.iter().enumerate() {
+            letters.push(*vowel);
+            if let (true, Some(tone)) = (i == position, tone) {
+                letters.push(tone.char());
+            }
+        }
+        letters.extend(final_consonant.chars());
+        Some(compose_vec(letters).iter().collect())
+    }
+}
+
+impl KbdRule for SyllableRule {
+    fn inputs(&self) -> Vec<String> {
+        let method = self.method;
+        let mut rimes: Vec<String> = FINALS.iter().map(|f| format!("gi{}", f)).collect();
+        rimes.push("gi".to_string());
+        for keys in self.nuclei.keys() {
+            for initial in [""].iter().chain(GLIDE_INITIALS.iter()) {
+                for final_consonant in [""].iter().chain(FINALS.iter()) {
+                    rimes.push(format!("{}{}{}", initial, keys, final_consonant));
+                }
+            }
+        }
+        let mut inputs = Vec::new();
+        let mut listed: BTreeSet<String> = BTreeSet::new();
+        for rime in rimes {
+            let toned = method.tone_keys().map(|key| format!("{}{}", rime, key));
+            for seq in std::iter::once(rime).chain(toned) {
+                // a rime without a mark or a tone types itself
+                if self.apply(&seq).as_ref() == Some(&seq) {
+                    continue;
+                }
+                for case in [seq.clone(), capitalize(&seq), seq.to_uppercase()] {
+                    if self.apply(&case).is_some() && listed.insert(case.clone()) {
+                        inputs.push(case);
+                    }
+                }
+            }
+        }
+        inputs
+    }
+
+    fn apply(&self, seq: &str) -> Option<String> {
+        let lower = seq.to_lowercase();
+        let syllable = self.syllable(&lower)?;
+        // lowercase, capitalized, or all in capitals
+        if seq == lower {
+            Some(syllable)
+        } else if seq == capitalize(&lower) {
+            Some(capitalize(&syllable))
+        } else if seq == seq.to_uppercase() {
+            Some(syllable.to_uppercase())
+        } else {
+            None
+        }
+    }
+}
+
+/// The lowercase key sequence, and the capital with either case for the keys that follow
+fn cases(seq: &str) -> Vec<(String, bool)> {
+    let mut chars = seq.chars();
+    let first = chars.next().unwrap();
+    let rest: String = chars.collect();
+    let mut sequences = vec![(seq.to_string(), false)];
+    for rest in [rest.clone(), rest.to_uppercase()] {
+        let capital: String = first.to_uppercase().chain(rest.chars()).collect();
+        if !sequences.contains(&(capital.clone(), true)) {
+            sequences.push((capital, true));
+        }
+    }
+    sequences
+}
+
+fn add_cases(map: &mut KbdMap, seq: &str, letters: Vec<char>) {
+    for (seq, capital) in cases(seq) {
+        let mut letters = letters.clone();
+        if capital {
+            letters[0] = letters[0].to_uppercase().next().unwrap();
+        }
+        map.add(seq, compose_vec(letters).iter().collect());
+    }
+}
+
+pub fn gen(keyboard: &mut KbdWriter, method: Method) {
+    keyboard.set_metadata(KbdMetaData::new(
+        "Vietnamese".to_string(),
+        "vi".to_string(),
+        match method {
+            Method::Telex => "Vietnamese typed with Telex".to_string(),
+            Method::Vni => "Vietnamese typed with VNI".to_string(),
+        },
+    ));
+
+    // sắc, huyền, hỏi, ngã, nặng
+    let tones: [Diacritic; 5] = [
+        Diacritic::Acute,
+        Diacritic::Grave,
+        Diacritic::HookAbove,
+        Diacritic::Tilde,
+        Diacritic::DotBelow,
+    ];
+
+    // modifier keys
+    for (key, tone) in method.tone_keys().iter().zip(tones.iter()) {
+        keyboard.add_modifier(*key, tone.name().to_string());
+    }
+    match method {
+        Method::Telex => {
+            keyboard.add_modifier('w', "breve, horn".to_string());
+        }
+        Method::Vni => {
+            keyboard.add_modifier('6', Diacritic::Circumflex.name().to_string());
+            keyboard.add_modifier('7', Diacritic::Horn.name().to_string());
+            keyboard.add_modifier('8', Diacritic::Breve.name().to_string());
+            keyboard.add_modifier('9', "stroke".to_string());
+        }
+    }
+
+    // vowel marks, and the tone typed at the end of the syllable
+    keyboard.write_rule("syllables".to_string(), SyllableRule::new(method, tones));
+
+    // d with stroke
+    let mut consonant_map = KbdMap::new();
+    add_cases(
+        &mut consonant_map,
+        &['d', method.stroke_key()].iter().collect::<String>(),
+        vec!['đ'],
+    );
+    keyboard.write_section("consonants".to_string(), consonant_map);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn telex() {
+        let mut kbd = KbdWriter::new();
+        gen(&mut kbd, Method::Telex);
+        assert_eq!(kbd.lookup("dd"), Some("đ"));
+        assert_eq!(kbd.lookup("Dd"), Some("Đ"));
+        for (seq, syllable) in [
+            ("aas", "ấ"),
+            ("w", "ư"),
+            ("uow", "ươ"),
+            ("Uow", "Ươ"),
+            ("uowf", "ườ"),
+            ("tieengs", "tiếng"),
+            ("Vieetj", "Việt"),
+            ("VIEETJ", "VIỆT"),
+            ("nguwowif", "người"),
+            ("mwaf", "mừa"),
+            ("hoaf", "hoà"),
+            ("muaf", "mùa"),
+            ("quas", "quá"),
+            ("gias", "giá"),
+            ("gif", "gì"),
+            ("ddaays", "đấy"),
+        ] {
+            assert_eq!(kbd.apply_rules(seq), Some(syllable.to_string()));
+        }
+        assert_eq!(kbd.apply_rules("vIEETJ"), None);
+        assert_eq!(kbd.apply_rules("hello"), None);
+    }
+
+    #[test]
+    fn vni() {
+        let mut kbd = KbdWriter::new();
+        gen(&mut kbd, Method::Vni);
+        assert_eq!(kbd.lookup("d9"), Some("đ"));
+        for (seq, syllable) in [
+            ("a61", "ấ"),
+            ("a8", "ă"),
+            ("uo7", "ươ"),
+            ("tie6ng1", "tiếng"),
+            ("Vie6t5", "Việt"),
+            ("d9a6y1", "đấy"),
+            ("hoa2", "hoà"),
+        ] {
+            assert_eq!(kbd.apply_rules(seq), Some(syllable.to_string()));
+        }
+        assert_eq!(kbd.apply_rules("w"), None);
+    }
+
+    #[test]
+    fn rimes() {
+        let rule = SyllableRule::new(Method::Telex, [Diacritic::Acute; 5]);
+        let inputs = rule.inputs();
+        for seq in ["ieengs", "Ieengs", "IEENGS", "w", "quas", "gif"] {
+            assert!(inputs.contains(&seq.to_string()));
+        }
+        // the initial consonants and rimes without marks or tones type themselves
+        assert!(!inputs.contains(&"tieengs".to_string()));
+        assert!(!inputs.contains(&"an".to_string()));
+    }
+}
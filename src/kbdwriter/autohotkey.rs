@@ -0,0 +1,182 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Display};
+
+use super::{KbdMetaData, KbdWriter};
+
+pub struct AutohotkeyKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> AutohotkeyKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+}
+
+/// Escapes a hotstring abbreviation
+fn escape_trigger(seq: &str) -> String {
+    let mut escaped = String::new();
+    for c in seq.chars() {
+        match c {
+            '`' | ';' | ':' => {
+                escaped.push('`');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes a hotstring replacement, which is sent as keystrokes
+fn escape_replacement(mapped: &str) -> String {
+    let mut escaped = String::new();
+    for c in mapped.chars() {
+        match c {
+            '`' | ';' => {
+                escaped.push('`');
+                escaped.push(c);
+            }
+            '{' | '}' | '!' | '#' | '^' | '+' => {
+                escaped.push('{');
+                escaped.push(c);
+                escaped.push('}');
+            }
+            ' ' => escaped.push_str("{Space}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// What is on screen after typing a key sequence. Every hotstring fires on its last key,
+/// the longest one first, and erases what typing its own keys put on screen
+fn typed(seq: &str, mappings: &HashMap<&str, &str>) -> String {
+    let chars: Vec<char> = seq.chars().collect();
+    let mut screen: Vec<char> = Vec::new();
+    for end in 1..=chars.len() {
+        screen.push(chars[end - 1]);
+        for start in 0..end {
+            let trigger: String = chars[start..end].iter().collect();
+            if let Some(mapped) = mappings.get(trigger.as_str()) {
+                let erased = backspaces(&trigger, mappings);
+                screen.truncate(screen.len().saturating_sub(erased));
+                screen.extend(mapped.chars());
+                break;
+            }
+        }
+    }
+    screen.into_iter().collect()
+}
+
+/// The characters a hotstring erases: what its keys before the last one typed, and the last key
+fn backspaces(trigger: &str, mappings: &HashMap<&str, &str>) -> usize {
+    let mut chars = trigger.chars();
+    chars.next_back();
+    typed(chars.as_str(), mappings).chars().count() + 1
+}
+
+impl<'a> Display for AutohotkeyKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        // Comment
+        writeln!(f, "; {} ({})", metadata.language, metadata.language_code)?;
+        writeln!(f, "; {}", metadata.description)?;
+        writeln!(f, "#Requires AutoHotkey v2.0")?;
+
+        // KEYMAP
+        // longer sequences come first so they take precedence over the sequences they end
+        // with. A sequence extending a shorter one fires after it, so nothing is erased
+        // automatically (B0) and each hotstring backspaces over what is on screen instead
+        let mut on_screen: HashMap<&str, &str> = HashMap::new();
+        for (_, keymap) in kbd.sections.iter() {
+            for (seq, mapped) in keymap.iter() {
+                on_screen.entry(seq.as_str()).or_insert(mapped.as_str());
+            }
+        }
+        let longest = kbd
+            .sections
+            .iter()
+            .flat_map(|(_, keymap)| keymap.iter().map(|(seq, _)| seq.chars().count()))
+            .max()
+            .unwrap_or(0);
+        for length in (1..=longest).rev() {
+            // sections
+            for (section, keymap) in kbd.sections.iter() {
+                let mut mappings = keymap
+                    .iter()
+                    .filter(|(seq, _)| seq.chars().count() == length)
+                    .peekable();
+                if mappings.peek().is_none() {
+                    continue;
+                }
+                writeln!(f, "; {} ({} keys)", section, length)?;
+                for (seq, mapped) in mappings {
+                    let case_sensitive = seq.to_lowercase() != seq.to_uppercase();
+                    writeln!(
+                        f,
+                        ":*?B0{}:{}::{{BS {}}}{}",
+                        if case_sensitive { "C" } else { "" },
+                        escape_trigger(seq),
+                        backspaces(seq, &on_screen),
+                        escape_replacement(mapped)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape_trigger("a;"), "a`;");
+        assert_eq!(escape_trigger("q:`"), "q`:``");
+        assert_eq!(escape_replacement("{ς }"), "{{}ς{Space}{}}");
+    }
+    #[test]
+    fn longest_first() {
+        let mut kbd = KbdWriter::new();
+        let mut short = KbdMap::new();
+        short.add("s".to_string(), "σ".to_string());
+        kbd.write_section("consonants".to_string(), short);
+        let mut long = KbdMap::new();
+        long.add("s ".to_string(), "ς ".to_string());
+        long.add("?".to_string(), ";".to_string());
+        kbd.write_section("final".to_string(), long);
+        let output = AutohotkeyKbdWriter::new(&kbd).to_string();
+        let lines: Vec<&str> = output.lines().skip(3).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "; final (2 keys)",
+                ":*?B0C:s ::{BS 2}ς{Space}",
+                "; consonants (1 keys)",
+                ":*?B0C:s::{BS 1}σ",
+                "; final (1 keys)",
+                ":*?B0:?::{BS 1}`;",
+            ]
+        );
+    }
+    #[test]
+    fn prefixes() {
+        let mappings = HashMap::from([("s", "σ"), ("s ", "ς "), ("ks", "ξ"), ("k", "κ")]);
+        assert_eq!(typed("s", &mappings), "σ");
+        assert_eq!(typed("s ", &mappings), "ς ");
+        assert_eq!(typed("ks", &mappings), "ξ");
+        // s replaces what s typed, and ks what k typed before it
+        assert_eq!(backspaces("s ", &mappings), 2);
+        assert_eq!(backspaces("ks", &mappings), 2);
+    }
+}
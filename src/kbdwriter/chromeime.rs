@@ -0,0 +1,268 @@
+use std::{cell::RefCell, fmt::Display};
+
+use convert_case::{Case, Casing};
+
+use crate::automaton::Automaton;
+
+use super::{KbdMetaData, KbdWriter};
+
+pub struct ChromeImeKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> ChromeImeKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+
+    /// The extension manifest, `manifest.json`
+    pub fn manifest(&self) -> String {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        let mut manifest = String::new();
+        manifest.push_str("{\n");
+        manifest.push_str("  \"manifest_version\": 3,\n");
+        manifest.push_str(&format!("  \"name\": {},\n", quote(&metadata.language)));
+        manifest.push_str("  \"version\": \"0.1.0\",\n");
+        manifest.push_str(&format!(
+            "  \"description\": {},\n",
+            quote(&metadata.description)
+        ));
+        manifest.push_str("  \"permissions\": [\"input\"],\n");
+        manifest.push_str("  \"background\": {\"service_worker\": \"background.js\"},\n");
+        manifest.push_str("  \"input_components\": [\n");
+        manifest.push_str("    {\n");
+        manifest.push_str(&format!("      \"name\": {},\n", quote(&metadata.language)));
+        manifest.push_str(&format!(
+            "      \"id\": {},\n",
+            quote(&format!(
+                "genkeyboard-{}",
+                metadata.language.to_case(Case::Kebab)
+            ))
+        ));
+        manifest.push_str(&format!(
+            "      \"language\": {},\n",
+            quote(&metadata.language_code)
+        ));
+        manifest.push_str("      \"layouts\": [\"us\"]\n");
+        manifest.push_str("    }\n");
+        manifest.push_str("  ]\n");
+        manifest.push_str("}\n");
+        manifest
+    }
+
+    /// Every file of the unpacked extension, the input method is written to `background.js`
+    pub fn files(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("manifest.json", self.manifest()),
+            ("background.js", self.to_string()),
+        ]
+    }
+}
+
+/// Quotes a string as a JSON string
+fn quote(str: &str) -> String {
+    let mut quoted = String::from('\"');
+    for c in str.chars() {
+        match c {
+            '\"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('\"');
+    quoted
+}
+
+const IME_SCRIPT: &str = r#"let contextId = -1;
+let state = 0;
+let typed = "";
+
+function reset() {
+    state = 0;
+    typed = "";
+}
+
+function composition() {
+    let accepting = automaton[state].a;
+    return accepting !== null ? accepting : typed;
+}
+
+function update() {
+    if (typed.length > 0) {
+        let text = composition();
+        chrome.input.ime.setComposition({contextID: contextId, text: text, cursor: text.length});
+    } else {
+        chrome.input.ime.clearComposition({contextID: contextId});
+    }
+}
+
+function commit() {
+    if (typed.length > 0) {
+        chrome.input.ime.commitText({contextID: contextId, text: composition()});
+    }
+    reset();
+}
+
+function step(key) {
+    let transitions = automaton[state].t;
+    if (transitions !== null && transitions.has(key)) {
+        state = transitions.get(key);
+        typed += key;
+        return true;
+    }
+    return false;
+}
+
+chrome.input.ime.onFocus.addListener((context) => {
+    contextId = context.contextID;
+    reset();
+});
+
+chrome.input.ime.onBlur.addListener(() => {
+    contextId = -1;
+    reset();
+});
+
+chrome.input.ime.onReset.addListener(() => {
+    reset();
+});
+
+chrome.input.ime.onKeyEvent.addListener((engineID, keyData) => {
+    if (keyData.type !== "keydown" || keyData.ctrlKey || keyData.altKey) {
+        return false;
+    }
+    if (keyData.key === "Backspace" && typed.length > 0) {
+        let retype = Array.from(typed).slice(0, -1);
+        reset();
+        for (const key of retype) {
+            step(key);
+        }
+        update();
+        return true;
+    }
+    if (Array.from(keyData.key).length !== 1) {
+        commit();
+        return false;
+    }
+    if (step(keyData.key)) {
+        update();
+        return true;
+    }
+    // no transition from the current state, start again from the start state
+    commit();
+    if (step(keyData.key)) {
+        update();
+        return true;
+    }
+    return false;
+});
+"#;
+
+impl<'a> Display for ChromeImeKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        // Comment
+        writeln!(f, "// {} ({})", metadata.language, metadata.language_code)?;
+        writeln!(f, "// {}", metadata.description)?;
+
+        // KEYMAP
+        let mut output = String::new();
+        Automaton::from_writer(&kbd).0.json_codegen(&mut output)?;
+        writeln!(f, "{}", output)?;
+        // input method
+        write!(f, "{}", IME_SCRIPT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    /// Checks brackets are balanced outside of string literals and comments
+    fn balanced(src: &str) -> bool {
+        let mut stack = Vec::new();
+        let mut chars = src.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\"' => loop {
+                    match chars.next() {
+                        Some('\\') => {
+                            chars.next();
+                        }
+                        Some('\"') => break,
+                        Some('\n') | None => return false,
+                        _ => {}
+                    }
+                },
+                '/' if chars.peek() == Some(&'/') => {
+                    for c in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                '(' | '[' | '{' => stack.push(c),
+                ')' | ']' | '}' => {
+                    let open = match c {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    if stack.pop() != Some(open) {
+                        return false;
+                    }
+                }
+                _ => {}
+            }
+        }
+        stack.is_empty()
+    }
+
+    fn writer() -> KbdWriter {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("a".to_string(), "α".to_string());
+        map.add("a\\".to_string(), "ά\"".to_string());
+        map.add("{".to_string(), "}".to_string());
+        kbd.write_section("test".to_string(), map);
+        kbd
+    }
+
+    #[test]
+    fn manifest() {
+        let kbd = writer();
+        let manifest = ChromeImeKbdWriter::new(&kbd).manifest();
+        assert!(balanced(&manifest));
+        for key in [
+            "\"manifest_version\": 3",
+            "\"permissions\": [\"input\"]",
+            "\"service_worker\": \"background.js\"",
+            "\"input_components\"",
+        ] {
+            assert!(manifest.contains(key));
+        }
+    }
+    #[test]
+    fn background() {
+        let kbd = writer();
+        let script = ChromeImeKbdWriter::new(&kbd).to_string();
+        assert!(balanced(&script));
+        assert!(script.contains("[\"\\\\\", 2]"));
+        assert!(script.contains("a: \"ά\\\"\""));
+        assert!(script.contains("chrome.input.ime.onKeyEvent.addListener"));
+    }
+}
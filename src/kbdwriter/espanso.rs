@@ -0,0 +1,168 @@
+use std::{cell::RefCell, collections::BTreeSet, fmt::Display};
+
+use convert_case::{Case, Casing};
+
+use super::{KbdMetaData, KbdWriter};
+
+pub struct EspansoKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> EspansoKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+
+    /// The package manifest, `_manifest.yml`
+    pub fn manifest(&self) -> String {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        let mut manifest = String::new();
+        manifest.push_str(&format!(
+            "name: {}\n",
+            quote(&format!(
+                "genkeyboard-{}",
+                metadata.language.to_case(Case::Kebab)
+            ))
+        ));
+        manifest.push_str(&format!("title: {}\n", quote(&metadata.language)));
+        manifest.push_str(&format!("description: {}\n", quote(&metadata.description)));
+        manifest.push_str("version: 0.1.0\n");
+        manifest.push_str("author: genkeyboard\n");
+        manifest
+    }
+
+    /// Mappings left out because a shorter trigger they start with expands first
+    pub fn dropped(&self) -> Vec<(String, String)> {
+        let kbd = self.kbd.borrow();
+        let triggers: BTreeSet<&str> = kbd
+            .sections
+            .iter()
+            .flat_map(|(_, keymap)| keymap.iter().map(|(seq, _)| seq.as_str()))
+            .collect();
+        let mut dropped = Vec::new();
+        for (_, keymap) in kbd.sections.iter() {
+            for (seq, mapped) in keymap.iter() {
+                if seq
+                    .char_indices()
+                    .skip(1)
+                    .any(|(i, _)| triggers.contains(&seq[..i]))
+                {
+                    dropped.push((seq.clone(), mapped.clone()));
+                }
+            }
+        }
+        dropped
+    }
+
+    /// Every file of the package, the matches are written to `package.yml`
+    pub fn files(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("_manifest.yml", self.manifest()),
+            ("package.yml", self.to_string()),
+        ]
+    }
+}
+
+/// Quotes a string as a double-quoted YAML scalar
+fn quote(str: &str) -> String {
+    let mut quoted = String::from('\"');
+    for c in str.chars() {
+        match c {
+            '\"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\t' => quoted.push_str("\\t"),
+            '\n' => quoted.push_str("\\n"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('\"');
+    quoted
+}
+
+impl<'a> Display for EspansoKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        // Comment
+        writeln!(f, "# {} ({})", metadata.language, metadata.language_code)?;
+        writeln!(f, "# {}", metadata.description)?;
+
+        // KEYMAP
+        // header
+        writeln!(f, "matches:")?;
+        // sections
+        let dropped: BTreeSet<String> = self.dropped().into_iter().map(|(seq, _)| seq).collect();
+        for (section, keymap) in kbd.sections.iter() {
+            writeln!(f, "  # {}", section)?;
+            for (seq, mapped) in keymap.iter().filter(|(seq, _)| !dropped.contains(*seq)) {
+                writeln!(f, "  - trigger: {}", quote(seq))?;
+                writeln!(f, "    replace: {}", quote(mapped))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("\"a\\"), "\"\\\"a\\\\\"");
+    }
+    #[test]
+    fn package() {
+        let mut kbd = KbdWriter::new();
+        kbd.set_metadata(KbdMetaData::new(
+            "Lingua Latina".to_string(),
+            "la".to_string(),
+            "Latin with macrons".to_string(),
+        ));
+        let mut map = KbdMap::new();
+        map.add("a;".to_string(), "ā".to_string());
+        kbd.write_section("macrons".to_string(), map);
+        let files = EspansoKbdWriter::new(&kbd).files();
+        assert_eq!(files[0].0, "_manifest.yml");
+        assert!(files[0]
+            .1
+            .starts_with("name: \"genkeyboard-lingua-latina\"\n"));
+        assert_eq!(files[1].0, "package.yml");
+        assert!(files[1]
+            .1
+            .ends_with("matches:\n  # macrons\n  - trigger: \"a;\"\n    replace: \"ā\"\n"));
+    }
+    #[test]
+    fn prefixes() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("s".to_string(), "с".to_string());
+        map.add("sh".to_string(), "ш".to_string());
+        map.add("sch".to_string(), "щ".to_string());
+        map.add("ch".to_string(), "ч".to_string());
+        kbd.write_section("consonants".to_string(), map);
+        let writer = EspansoKbdWriter::new(&kbd);
+        assert_eq!(
+            writer.dropped(),
+            vec![
+                ("sch".to_string(), "щ".to_string()),
+                ("sh".to_string(), "ш".to_string())
+            ]
+        );
+        let package = writer.to_string();
+        assert!(package.contains("trigger: \"ch\""));
+        assert!(!package.contains("trigger: \"sh\""));
+    }
+}
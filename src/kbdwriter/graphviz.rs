@@ -6,12 +6,16 @@ use super::KbdWriter;
 
 pub struct GraphVizKbdWriter<'a> {
     kbd: RefCell<&'a KbdWriter>,
+    style: AutomatonStyle,
+    collapse_intermediate: bool,
 }
 
 impl<'a> GraphVizKbdWriter<'a> {
-    pub fn new(kbd: &'a KbdWriter) -> Self {
+    pub fn new(kbd: &'a KbdWriter, style: AutomatonStyle, collapse_intermediate: bool) -> Self {
         Self {
             kbd: RefCell::new(kbd),
+            style,
+            collapse_intermediate,
         }
     }
 }
@@ -23,8 +27,13 @@ impl<'a> Display for GraphVizKbdWriter<'a> {
 
         // Generate automaton and use it to generate graph
         let (automaton, ranges) = Automaton::from_writer(&kbd);
-        let style = AutomatonStyle::default();
-        automaton.write(&mut output, ranges, *kbd, style)?;
+        automaton.write(
+            &mut output,
+            ranges,
+            *kbd,
+            &self.style,
+            self.collapse_intermediate,
+        )?;
         write!(f, "{}", output)
     }
 }
@@ -0,0 +1,155 @@
+use std::{cell::RefCell, fmt::Display};
+
+use crate::compose::{diacritics, Diacritic};
+
+use super::{list::with_base, KbdMetaData, KbdWriter};
+
+pub struct HtmlKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> HtmlKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+}
+
+/// Escapes text for use in html content and attributes
+pub(crate) fn escape(str: &str) -> String {
+    let mut escaped = String::new();
+    for c in str.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const STYLE: &str = r#"body {
+    font-family: "GFS Didot", "Gentium Plus", "Noto Serif", "New Athena Unicode", "Palatino Linotype", "Times New Roman", serif;
+    margin: 2em;
+}
+table { border-collapse: collapse; margin-bottom: 1.5em; }
+th, td { border: 1px solid #999; padding: 0.2em 0.8em; text-align: left; }
+td.out { font-size: 1.3em; }
+kbd { font-family: "DejaVu Sans Mono", "Noto Sans Mono", monospace; }
+#filter { font-size: 1.1em; margin-bottom: 1em; width: 20em; }
+@media print {
+    #filter { display: none; }
+    h2 { break-after: avoid; }
+    tr { break-inside: avoid; }
+}"#;
+
+const SCRIPT: &str = r#"document.getElementById("filter").addEventListener("input", (event) => {
+    let query = event.target.value.normalize("NFC");
+    for (const row of document.querySelectorAll("tbody tr")) {
+        row.hidden = query !== "" && !row.textContent.normalize("NFC").includes(query);
+    }
+    for (const section of document.querySelectorAll("section")) {
+        section.hidden = section.querySelectorAll("tbody tr:not([hidden])").length === 0;
+    }
+});"#;
+
+impl<'a> Display for HtmlKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        // LANGUAGE DEFINE
+        writeln!(f, "<!DOCTYPE html>")?;
+        writeln!(f, "<html lang=\"{}\">", escape(&metadata.language_code))?;
+        writeln!(f, "<head>")?;
+        writeln!(f, "<meta charset=\"utf-8\">")?;
+        writeln!(f, "<title>{}</title>", escape(&metadata.language))?;
+        writeln!(f, "<style>\n{}\n</style>", STYLE)?;
+        writeln!(f, "</head>")?;
+        writeln!(f, "<body>")?;
+        writeln!(f, "<header>")?;
+        writeln!(
+            f,
+            "<h1>{} ({})</h1>",
+            escape(&metadata.language),
+            escape(&metadata.language_code)
+        )?;
+        writeln!(f, "<p>{}</p>", escape(&metadata.description))?;
+        writeln!(f, "</header>")?;
+        writeln!(
+            f,
+            "<input id=\"filter\" type=\"search\" placeholder=\"Filter by keys or output\">"
+        )?;
+
+        // KEYMAP
+        // sections
+        for (section, keymap) in kbd.sections.iter() {
+            writeln!(f, "<section>")?;
+            writeln!(f, "<h2>{}</h2>", escape(section))?;
+            writeln!(f, "<table>")?;
+            writeln!(
+                f,
+                "<thead><tr><th>Keys</th><th>Output</th><th>Diacritics</th></tr></thead>"
+            )?;
+            writeln!(f, "<tbody>")?;
+            for (seq, mapped) in keymap.iter() {
+                let names: Vec<&str> = diacritics(mapped)
+                    .into_iter()
+                    .map(Diacritic::name)
+                    .collect();
+                writeln!(
+                    f,
+                    "<tr><td><kbd>{}</kbd></td><td class=\"out\"><bdi>{}</bdi></td><td>{}</td></tr>",
+                    escape(seq),
+                    escape(&with_base(mapped)),
+                    escape(&names.join(", "))
+                )?;
+            }
+            writeln!(f, "</tbody>")?;
+            writeln!(f, "</table>")?;
+            writeln!(f, "</section>")?;
+        }
+        // footer
+        writeln!(f, "<script>\n{}\n</script>", SCRIPT)?;
+        writeln!(f, "</body>")?;
+        writeln!(f, "</html>")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn sections() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("q<".to_string(), "«".to_string());
+        kbd.write_section("punctuation".to_string(), map);
+        let output = HtmlKbdWriter::new(&kbd).to_string();
+        assert!(output.contains("<h2>punctuation</h2>"));
+        assert!(output.contains(
+            "<tr><td><kbd>q&lt;</kbd></td><td class=\"out\"><bdi>«</bdi></td><td></td></tr>"
+        ));
+        assert_eq!(output.matches("<table>").count(), 1);
+    }
+    #[test]
+    fn diacritic_names() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("a;:".to_string(), "ἄ".to_string());
+        kbd.write_section("accents".to_string(), map);
+        let output = HtmlKbdWriter::new(&kbd).to_string();
+        assert!(output
+            .contains("<td class=\"out\"><bdi>ἄ</bdi></td><td>smooth breathing, acute</td></tr>"));
+    }
+}
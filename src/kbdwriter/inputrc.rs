@@ -0,0 +1,119 @@
+use std::{cell::RefCell, collections::BTreeSet, fmt::Display};
+
+use super::{KbdMetaData, KbdWriter};
+
+pub struct InputrcKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> InputrcKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+
+    /// Mappings left out because readline types a macro as keys, which would map it again
+    pub fn dropped(&self) -> Vec<(String, String)> {
+        self.kbd.borrow().retyped()
+    }
+}
+
+/// Escapes a string for use inside a double-quoted readline key sequence or macro
+fn escape(str: &str) -> String {
+    let mut escaped = String::new();
+    for c in str.chars() {
+        match c {
+            '\\' | '\"' | '\'' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_ascii_control() => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl<'a> Display for InputrcKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        let application = metadata.identifier();
+        let retyped = self.dropped();
+        let dropped: BTreeSet<(&str, &str)> = retyped
+            .iter()
+            .map(|(seq, mapped)| (seq.as_str(), mapped.as_str()))
+            .collect();
+
+        // Comment
+        writeln!(f, "# {} ({})", metadata.language, metadata.language_code)?;
+        writeln!(f, "# {}", metadata.description)?;
+        writeln!(
+            f,
+            "# Bound only in programs whose readline name is {}, like `rlwrap -C {} <command>`.",
+            application, application
+        )?;
+        writeln!(
+            f,
+            "# Remove the $if and $endif lines to bind it in every program, bash included."
+        )?;
+        writeln!(f, "$if {}", application)?;
+        // pass multibyte characters through instead of treating them as meta keys
+        writeln!(f, "set input-meta on")?;
+        writeln!(f, "set output-meta on")?;
+        writeln!(f, "set convert-meta off")?;
+
+        // KEYMAP
+        // sections
+        for (section, keymap) in kbd.sections.iter() {
+            writeln!(f, "# {}", section)?;
+            for (seq, mapped) in keymap.iter() {
+                if dropped.contains(&(seq.as_str(), mapped.as_str())) {
+                    continue;
+                }
+                writeln!(f, "\"{}\": \"{}\"", escape(seq), escape(mapped))?;
+            }
+        }
+        writeln!(f, "$endif")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("a\\"), "a\\\\");
+        assert_eq!(escape("\"'"), "\\\"\\'");
+        assert_eq!(escape("\u{1b}"), "\\033");
+        assert_eq!(escape("ά"), "ά");
+    }
+
+    #[test]
+    fn guarded() {
+        let mut kbd = KbdWriter::new();
+        kbd.set_metadata(KbdMetaData::new(
+            "Esperanto".to_string(),
+            "eo".to_string(),
+            "".to_string(),
+        ));
+        let mut map = KbdMap::new();
+        map.add("cx".to_string(), "ĉ".to_string());
+        map.add("cxx".to_string(), "cx".to_string());
+        kbd.write_section("x-system".to_string(), map);
+        let output = InputrcKbdWriter::new(&kbd).to_string();
+        assert!(output.contains("$if esperanto\n"));
+        assert!(output.contains("\"cx\": \"ĉ\"\n"));
+        // cx would be typed as keys and turn into ĉ again
+        assert!(!output.contains("\"cxx\""));
+        assert!(output.ends_with("$endif\n"));
+    }
+}
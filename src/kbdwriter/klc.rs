@@ -0,0 +1,272 @@
+use std::{
+    cell::RefCell,
+    collections::{btree_map::Entry, BTreeMap},
+    fmt::Display,
+};
+
+use super::{qwerty, KbdMetaData, KbdWriter};
+
+pub struct KlcKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+/// The parts of a layout that MSKLC can express: single keys and one dead key deep
+#[derive(Default)]
+struct KlcLayout {
+    keys: BTreeMap<char, char>,
+    dead_keys: BTreeMap<char, BTreeMap<char, char>>,
+    dropped: Vec<(String, String)>,
+}
+
+impl<'a> KlcKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+
+    /// Mappings which cannot be expressed as keys or dead keys, postfix sequences among them
+    /// as only modifier keys become dead keys
+    pub fn dropped(&self) -> Vec<(String, String)> {
+        self.layout().dropped
+    }
+
+    fn layout(&self) -> KlcLayout {
+        let kbd = self.kbd.borrow();
+        let mut layout = KlcLayout::default();
+        let single_char = |str: &str| -> Option<char> {
+            let mut chars = str.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if (c as u32) <= 0xffff => Some(c),
+                _ => None,
+            }
+        };
+
+        // keys which produce a character by themselves
+        for (_, keymap) in kbd.sections.iter() {
+            for (seq, mapped) in keymap.iter() {
+                if let (Some(key), Some(out)) = (single_char(seq), single_char(mapped)) {
+                    if qwerty::find(key).is_some() && !layout.keys.contains_key(&key) {
+                        layout.keys.insert(key, out);
+                        continue;
+                    }
+                }
+                if seq.chars().count() == 1 {
+                    layout.dropped.push((seq.clone(), mapped.clone()));
+                }
+            }
+        }
+
+        // dead keys followed by one key, a letter typed before its modifier stays a letter
+        for (_, keymap) in kbd.sections.iter() {
+            for (seq, mapped) in keymap.iter() {
+                let chars: Vec<char> = seq.chars().collect();
+                if chars.len() == 1 {
+                    continue;
+                }
+                let expressible = chars.len() == 2
+                    && qwerty::find(chars[0]).is_some()
+                    && qwerty::find(chars[1]).is_some()
+                    && kbd.modifiers.contains_key(&chars[0])
+                    && !kbd.modifiers.contains_key(&chars[1])
+                    && !layout.keys.contains_key(&chars[0]);
+                if let (true, Some(out)) = (expressible, single_char(mapped)) {
+                    let next = *layout.keys.get(&chars[1]).unwrap_or(&chars[1]);
+                    let dead_key = layout.dead_keys.entry(chars[0]).or_default();
+                    if let Entry::Vacant(e) = dead_key.entry(next) {
+                        e.insert(out);
+                        continue;
+                    }
+                }
+                layout.dropped.push((seq.clone(), mapped.clone()));
+            }
+        }
+
+        // a dead key followed by space produces itself
+        for (dead, dead_key) in layout.dead_keys.iter_mut() {
+            dead_key.entry(' ').or_insert(*dead);
+        }
+        layout
+    }
+}
+
+fn klc_char(c: char, dead: bool) -> String {
+    match dead {
+        true => format!("{:04x}@", c as u32),
+        false => format!("{:04x}", c as u32),
+    }
+}
+
+/// Encodes text as UTF-16LE with a byte order mark and CRLF line endings, as MSKLC expects
+pub fn encode_utf16(text: &str) -> Vec<u8> {
+    let mut bytes = vec![0xff, 0xfe];
+    for unit in text.replace('\n', "\r\n").encode_utf16() {
+        bytes.extend_from_slice(&unit.to_le_bytes());
+    }
+    bytes
+}
+
+impl<'a> Display for KlcKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        let layout = self.layout();
+
+        // LANGUAGE DEFINE
+        let name: String = metadata
+            .language_code
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .take(5)
+            .collect();
+        writeln!(
+            f,
+            "KBD\tkbd{}\t\"{}\"",
+            name.to_lowercase(),
+            metadata.language
+        )?;
+        writeln!(f)?;
+        writeln!(f, "COPYRIGHT\t\"(c) genkeyboard\"")?;
+        writeln!(f)?;
+        writeln!(f, "COMPANY\t\"genkeyboard\"")?;
+        writeln!(f)?;
+        writeln!(f, "LOCALENAME\t\"{}\"", metadata.language_code)?;
+        writeln!(f)?;
+        writeln!(f, "LOCALEID\t\"00001000\"")?;
+        writeln!(f)?;
+        writeln!(f, "VERSION\t1.0")?;
+        writeln!(f)?;
+        writeln!(f, "SHIFTSTATE")?;
+        writeln!(f)?;
+        writeln!(f, "0\t//Column 4")?;
+        writeln!(f, "1\t//Column 5 : Shft")?;
+        writeln!(f)?;
+
+        // KEYMAP
+        // header
+        writeln!(f, "LAYOUT\t\t;an extra '@' at the end is a dead key")?;
+        writeln!(f)?;
+        writeln!(f, "//SC\tVK_\t\tCap\t0\t1")?;
+        writeln!(f, "//--\t----\t\t----\t----\t----")?;
+        writeln!(f)?;
+        // keys
+        for key in qwerty::KEYS.iter() {
+            let base = *layout.keys.get(&key.base).unwrap_or(&key.base);
+            let shifted = *layout.keys.get(&key.shifted).unwrap_or(&key.shifted);
+            let cap = base.to_uppercase().eq(shifted.to_uppercase()) && base != shifted;
+            writeln!(
+                f,
+                "{:02x}\t{}\t\t{}\t{}\t{}\t// {}, {}",
+                key.scancode,
+                key.virtual_key,
+                cap as u8,
+                klc_char(base, layout.dead_keys.contains_key(&key.base)),
+                klc_char(shifted, layout.dead_keys.contains_key(&key.shifted)),
+                base,
+                shifted
+            )?;
+        }
+        writeln!(f)?;
+        // dead keys
+        for (dead, dead_key) in layout.dead_keys.iter() {
+            writeln!(f, "DEADKEY\t{}", klc_char(*dead, false))?;
+            writeln!(f)?;
+            for (next, mapped) in dead_key.iter() {
+                writeln!(
+                    f,
+                    "{}\t{}\t// {}{} -> {}",
+                    klc_char(*next, false),
+                    klc_char(*mapped, false),
+                    dead,
+                    next,
+                    mapped
+                )?;
+            }
+            writeln!(f)?;
+        }
+        // footer
+        writeln!(f, "DESCRIPTIONS")?;
+        writeln!(f)?;
+        writeln!(f, "0409\t{}", metadata.description)?;
+        writeln!(f)?;
+        writeln!(f, "LANGUAGENAMES")?;
+        writeln!(f)?;
+        writeln!(f, "0409\t{}", metadata.language)?;
+        writeln!(f)?;
+        writeln!(f, "ENDKBD")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    fn writer(mappings: &[(&str, &str)]) -> KbdWriter {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        for (seq, mapped) in mappings {
+            map.add(seq.to_string(), mapped.to_string());
+        }
+        kbd.write_section("test".to_string(), map);
+        kbd
+    }
+
+    #[test]
+    fn utf16() {
+        assert_eq!(
+            encode_utf16("ά\n"),
+            vec![0xff, 0xfe, 0xac, 0x03, b'\r', 0x00, b'\n', 0x00]
+        );
+    }
+    #[test]
+    fn dead_keys() {
+        let mut kbd = writer(&[(";a", "ā"), (";e", "ē")]);
+        kbd.add_modifier(';', "macron".to_string());
+        let output = KlcKbdWriter::new(&kbd).to_string();
+        assert!(output.contains("1e\tA\t\t1\t0061\t0041\t// a, A"));
+        assert!(output.contains(
+            "DEADKEY\t003b\n\n0020\t003b\t// ;  -> ;\n0061\t0101\t// ;a -> ā\n0065\t0113\t// ;e -> ē\n"
+        ));
+    }
+    #[test]
+    fn postfix() {
+        let mut kbd = writer(&[("a;", "ā"), ("e;", "ē"), ("hh", "ĥ")]);
+        kbd.add_modifier(';', "macron".to_string());
+        kbd.add_modifier('h', "circumflex".to_string());
+        let writer = KlcKbdWriter::new(&kbd);
+        assert!(!writer.to_string().contains("DEADKEY"));
+        assert_eq!(writer.dropped().len(), 3);
+    }
+    #[test]
+    fn remapped_next_key() {
+        let mut kbd = writer(&[("h", "η"), ("qh", "ῆ")]);
+        kbd.add_modifier('q', "circumflex".to_string());
+        let output = KlcKbdWriter::new(&kbd).to_string();
+        assert!(output.contains("03b7\t1fc6\t// qη -> ῆ"));
+    }
+    #[test]
+    fn dropped() {
+        let kbd = writer(&[
+            ("p", "π"),
+            ("h", "η"),
+            ("ph", "φ"),
+            ("s ", "ς "),
+            ("qab", "x"),
+        ]);
+        let dropped = KlcKbdWriter::new(&kbd).dropped();
+        assert_eq!(
+            dropped,
+            vec![
+                ("ph".to_string(), "φ".to_string()),
+                ("qab".to_string(), "x".to_string()),
+                ("s ".to_string(), "ς ".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::{cell::RefCell, fmt::Display};
+
+use super::{KbdMetaData, KbdWriter};
+
+pub struct LatexKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> LatexKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+}
+
+/// Escapes text for LaTeX
+fn escape(str: &str) -> String {
+    let mut escaped = String::new();
+    for c in str.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes text for a table cell, spaces are made visible
+fn escape_cell(str: &str) -> String {
+    escape(str).replace(' ', "\\textvisiblespace{}")
+}
+
+impl<'a> Display for LatexKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        // LANGUAGE DEFINE
+        writeln!(f, "% Compile with XeLaTeX or LuaLaTeX")?;
+        writeln!(f, "\\documentclass{{article}}")?;
+        writeln!(f, "\\usepackage{{fontspec}}")?;
+        writeln!(f, "\\usepackage{{longtable}}")?;
+        writeln!(
+            f,
+            "\\IfFontExistsTF{{GFS Didot}}{{\\setmainfont{{GFS Didot}}}}{{\\IfFontExistsTF{{Noto Serif}}{{\\setmainfont{{Noto Serif}}}}{{}}}}"
+        )?;
+        writeln!(
+            f,
+            "\\title{{{} ({})}}",
+            escape(&metadata.language),
+            escape(&metadata.language_code)
+        )?;
+        writeln!(f, "\\date{{}}")?;
+        writeln!(f, "\\begin{{document}}")?;
+        writeln!(f, "\\maketitle")?;
+        writeln!(f)?;
+        writeln!(f, "{}", escape(&metadata.description))?;
+
+        // KEYMAP
+        // sections
+        for (section, keymap) in kbd.sections.iter() {
+            writeln!(f)?;
+            writeln!(f, "\\section*{{{}}}", escape(section))?;
+            writeln!(f, "\\begin{{longtable}}{{ll}}")?;
+            writeln!(f, "\\hline")?;
+            writeln!(f, "Keys & Output \\\\")?;
+            writeln!(f, "\\hline")?;
+            writeln!(f, "\\endhead")?;
+            for (seq, mapped) in keymap.iter() {
+                writeln!(
+                    f,
+                    "\\texttt{{{}}} & {} \\\\",
+                    escape_cell(seq),
+                    escape_cell(mapped)
+                )?;
+            }
+            writeln!(f, "\\hline")?;
+            writeln!(f, "\\end{{longtable}}")?;
+        }
+        // footer
+        writeln!(f)?;
+        writeln!(f, "\\end{{document}}")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("a\\"), "a\\textbackslash{}");
+        assert_eq!(escape("&_%"), "\\&\\_\\%");
+        assert_eq!(escape("{^}"), "\\{\\textasciicircum{}\\}");
+        assert_eq!(escape_cell("s "), "s\\textvisiblespace{}");
+        assert_eq!(escape("ᾱ"), "ᾱ");
+    }
+}
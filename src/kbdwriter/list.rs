@@ -1,5 +1,7 @@
 use std::{cell::RefCell, fmt::Display};
 
+use crate::compose::{diacritics, Diacritic};
+
 use super::{KbdMetaData, KbdWriter};
 
 pub struct ListKbdWriter<'a> {
@@ -14,6 +16,34 @@ impl<'a> ListKbdWriter<'a> {
     }
 }
 
+/// Whether a string has right-to-left letters, Hebrew, Arabic and the other scripts around them
+pub(crate) fn is_rtl(str: &str) -> bool {
+    str.chars().any(|c| {
+        matches!(c,
+            '\u{590}'..='\u{8ff}'
+            | '\u{fb1d}'..='\u{fdff}'
+            | '\u{fe70}'..='\u{feff}'
+            | '\u{10800}'..='\u{10fff}'
+            | '\u{1e800}'..='\u{1efff}')
+    })
+}
+
+/// Puts a dotted circle before a combining mark that has no letter to sit on
+pub(crate) fn with_base(str: &str) -> String {
+    match str.chars().next() {
+        Some(c) if unicode_normalization::char::is_combining_mark(c) => format!("\u{25cc}{}", str),
+        _ => str.to_string(),
+    }
+}
+
+/// Isolates right-to-left text so it does not reorder the text around it
+fn isolate(str: &str) -> String {
+    match is_rtl(str) {
+        true => format!("\u{2068}{}\u{2069}", str),
+        false => str.to_string(),
+    }
+}
+
 impl<'a> Display for ListKbdWriter<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let kbd = self.kbd.borrow();
@@ -33,9 +63,37 @@ impl<'a> Display for ListKbdWriter<'a> {
         for (section, keymap) in kbd.sections.iter() {
             writeln!(f, "  {}", section)?;
             for (seq, mapped) in keymap.iter() {
-                writeln!(f, "    〈{}〉 → 〈{}〉", seq, mapped)?;
+                let shown = isolate(&with_base(mapped));
+                let names: Vec<&str> = diacritics(mapped)
+                    .into_iter()
+                    .map(Diacritic::name)
+                    .collect();
+                match names.is_empty() {
+                    true => writeln!(f, "    〈{}〉 → 〈{}〉", seq, shown)?,
+                    false => writeln!(f, "    〈{}〉 → 〈{}〉 ({})", seq, shown, names.join(", "))?,
+                }
             }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn right_to_left() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("k ".to_string(), "ך ".to_string());
+        map.add(";a".to_string(), "\u{5b7}".to_string());
+        map.add("a".to_string(), "α".to_string());
+        kbd.write_section("test".to_string(), map);
+        let output = ListKbdWriter::new(&kbd).to_string();
+        assert!(output.contains("〈k 〉 → 〈\u{2068}ך \u{2069}〉\n"));
+        assert!(output.contains("〈;a〉 → 〈\u{2068}\u{25cc}\u{5b7}\u{2069}〉 (patah)\n"));
+        assert!(output.contains("〈a〉 → 〈α〉\n"));
+    }
+}
@@ -0,0 +1,84 @@
+use std::{cell::RefCell, fmt::Display};
+
+use super::{KbdMetaData, KbdWriter};
+
+pub struct MarkdownKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> MarkdownKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+}
+
+/// Escapes the markdown punctuation in a string
+fn escape(str: &str) -> String {
+    let mut escaped = String::new();
+    for c in str.chars() {
+        match c {
+            c if c.is_ascii_punctuation() => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes text for a markdown table cell, spaces are made visible
+fn escape_cell(str: &str) -> String {
+    escape(str).replace(' ', "␣")
+}
+
+impl<'a> Display for MarkdownKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        // LANGUAGE DEFINE
+        writeln!(
+            f,
+            "# {} ({})",
+            escape(&metadata.language),
+            escape(&metadata.language_code)
+        )?;
+        writeln!(f)?;
+        writeln!(f, "{}", escape(&metadata.description))?;
+
+        // KEYMAP
+        // sections
+        for (section, keymap) in kbd.sections.iter() {
+            writeln!(f)?;
+            writeln!(f, "## {}", escape(section))?;
+            writeln!(f)?;
+            writeln!(f, "| Keys | Output |")?;
+            writeln!(f, "| --- | --- |")?;
+            for (seq, mapped) in keymap.iter() {
+                writeln!(f, "| {} | {} |", escape_cell(seq), escape_cell(mapped))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("a|b\\"), "a\\|b\\\\");
+        assert_eq!(escape("w_*`"), "w\\_\\*\\`");
+        assert_eq!(escape("ᾱ"), "ᾱ");
+        assert_eq!(escape("tone letters"), "tone letters");
+        assert_eq!(escape_cell("s "), "s␣");
+        assert_eq!(escape_cell("_ "), "\\_␣");
+    }
+}
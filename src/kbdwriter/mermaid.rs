@@ -0,0 +1,29 @@
+use std::{cell::RefCell, fmt::Display};
+
+use crate::automaton::Automaton;
+
+use super::KbdWriter;
+
+pub struct MermaidKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> MermaidKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+}
+
+impl<'a> Display for MermaidKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+        let mut output = String::new();
+
+        // Generate automaton and use it to generate the state diagram
+        let (automaton, ranges) = Automaton::from_writer(&kbd);
+        automaton.write_mermaid(&mut output, ranges, *kbd)?;
+        write!(f, "{}", output)
+    }
+}
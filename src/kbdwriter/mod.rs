@@ -1,23 +1,39 @@
 use std::{
     collections::{
         btree_map::{Entry, Iter},
-        BTreeMap,
+        BTreeMap, BTreeSet,
     },
     fmt,
+    rc::Rc,
 };
 
+use convert_case::{Case, Casing};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use unicode_normalization::UnicodeNormalization;
 use variant_count::VariantCount;
 
+pub mod autohotkey;
+pub mod chromeime;
 pub mod emacs;
+pub mod espanso;
 pub mod graphviz;
+pub mod html;
+pub mod inputrc;
+pub mod klc;
+pub mod latex;
 pub mod list;
+pub mod markdown;
+pub mod mermaid;
+pub mod qwerty;
+pub mod svg;
 pub mod vimfn;
+pub mod zshbindkey;
 
 #[derive(
     clap::ArgEnum,
     Clone,
     Debug,
+    Default,
     enum_utils::FromStr,
     Eq,
     IntoPrimitive,
@@ -27,15 +43,28 @@ pub mod vimfn;
 )]
 #[repr(u8)]
 pub enum Format {
+    #[default]
     List,
     EmacsQuail,
     VimFn,
     GraphViz,
+    Klc,
+    Autohotkey,
+    Espanso,
+    ChromeIme,
+    Inputrc,
+    ZshBindkey,
+    Html,
+    Svg,
+    Markdown,
+    Latex,
+    Mermaid,
 }
 
-impl Default for Format {
-    fn default() -> Self {
-        Format::List
+impl Format {
+    /// Whether the format lists every key sequence, so rules are expanded into tables for it
+    pub fn lists_sequences(&self) -> bool {
+        !matches!(self, Format::Svg)
     }
 }
 
@@ -45,6 +74,24 @@ impl fmt::Display for Format {
     }
 }
 
+/// Unicode normalization form applied to every mapped output
+#[derive(clap::ArgEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Normalization {
+    Nfc,
+    Nfd,
+    Nfkc,
+}
+
+impl Normalization {
+    pub fn apply(self, str: &str) -> String {
+        match self {
+            Normalization::Nfc => str.nfc().collect(),
+            Normalization::Nfd => str.nfd().collect(),
+            Normalization::Nfkc => str.nfkc().collect(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct KbdMetaData {
     pub language: String,
@@ -60,6 +107,15 @@ impl KbdMetaData {
             description,
         }
     }
+
+    /// The language as an identifier, like `ancient-greek`
+    pub(crate) fn identifier(&self) -> String {
+        self.language
+            .to_case(Case::Kebab)
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect()
+    }
 }
 
 impl Default for KbdMetaData {
@@ -75,6 +131,10 @@ impl Default for KbdMetaData {
 pub struct KbdWriter {
     metadata: Option<KbdMetaData>,
     pub(crate) sections: Vec<(String, KbdMap)>,
+    pub(crate) modifiers: BTreeMap<char, String>,
+    /// Sections produced by rule, with the index of the table section they come before
+    rules: Vec<(usize, String, Rc<dyn KbdRule>)>,
+    normalization: Option<Normalization>,
 }
 
 impl KbdWriter {
@@ -82,6 +142,9 @@ impl KbdWriter {
         Self {
             metadata: None,
             sections: Vec::new(),
+            modifiers: BTreeMap::new(),
+            rules: Vec::new(),
+            normalization: None,
         }
     }
     pub fn set_metadata(&mut self, metadata: KbdMetaData) {
@@ -90,22 +153,104 @@ impl KbdWriter {
     pub fn write_section(&mut self, title: String, keymap: KbdMap) {
         self.sections.push((title, keymap));
     }
-
-    pub fn subsection_writer(&self, section: &String) -> Option<KbdWriter> {
-        for (name, kbdmap) in self.sections.iter() {
-            if name == section {
-                let mut new_writer = KbdWriter::new();
-                new_writer.write_section(name.clone(), kbdmap.clone());
-                if let Some(metadata) = &self.metadata {
-                    new_writer.set_metadata(metadata.clone());
+    /// Describes what a modifier key contributes to the sequences it appears in
+    pub fn add_modifier(&mut self, key: char, description: String) {
+        self.modifiers.insert(key, description);
+    }
+    /// Mappings whose output contains a key that starts a sequence. Formats that type
+    /// the output as keys would map it again
+    pub(crate) fn retyped(&self) -> Vec<(String, String)> {
+        let first_keys: BTreeSet<char> = self
+            .sections
+            .iter()
+            .flat_map(|(_, keymap)| keymap.iter())
+            .filter_map(|(seq, _)| seq.chars().next())
+            .collect();
+        self.sections
+            .iter()
+            .flat_map(|(_, keymap)| keymap.iter())
+            .filter(|(_, mapped)| mapped.chars().any(|c| first_keys.contains(&c)))
+            .map(|(seq, mapped)| (seq.clone(), mapped.clone()))
+            .collect()
+    }
+    /// The output of a key sequence in any table section
+    #[cfg(test)]
+    pub(crate) fn lookup(&self, seq: &str) -> Option<&str> {
+        self.sections
+            .iter()
+            .find_map(|(_, keymap)| keymap.keymap.get(seq))
+            .map(|mapped| mapped.as_str())
+    }
+    /// Writes a section whose outputs are produced by rule when a key sequence is looked up
+    pub fn write_rule(&mut self, title: String, rule: impl KbdRule + 'static) {
+        self.rules.push((self.sections.len(), title, Rc::new(rule)));
+    }
+    /// Rewrites every mapped output in the given normalization form
+    pub fn normalize(&mut self, form: Normalization) {
+        for (_, keymap) in self.sections.iter_mut() {
+            for mapped in keymap.keymap.values_mut() {
+                *mapped = form.apply(mapped);
+            }
+        }
+        self.normalization = Some(form);
+    }
+    /// The names of the table and rule sections, in the order they were written
+    pub fn section_names(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self.sections.iter().map(|(name, _)| name).collect();
+        for (inserted, (index, name, _)) in self.rules.iter().enumerate() {
+            names.insert(index + inserted, name);
+        }
+        names
+    }
+    /// The output of the first rule accepting a key sequence
+    pub fn apply_rules(&self, seq: &str) -> Option<String> {
+        let mapped = self.rules.iter().find_map(|(_, _, rule)| rule.apply(seq))?;
+        match self.normalization {
+            Some(form) => Some(form.apply(&mapped)),
+            None => Some(mapped),
+        }
+    }
+    /// Turns every rule into a table section, for the formats that list every key sequence
+    pub fn enumerate_rules(&mut self) {
+        for (inserted, (index, name, rule)) in
+            std::mem::take(&mut self.rules).into_iter().enumerate()
+        {
+            let mut keymap = KbdMap::from_rule(rule.as_ref());
+            if let Some(form) = self.normalization {
+                for mapped in keymap.keymap.values_mut() {
+                    *mapped = form.apply(mapped);
                 }
-                return Some(new_writer);
             }
+            self.sections.insert(index + inserted, (name, keymap));
+        }
+    }
+
+    pub fn subsection_writer(&self, section: &String) -> Option<KbdWriter> {
+        let mut new_writer = KbdWriter::new();
+        if let Some((name, kbdmap)) = self.sections.iter().find(|(name, _)| name == section) {
+            new_writer.write_section(name.clone(), kbdmap.clone());
+        } else if let Some((_, name, rule)) = self.rules.iter().find(|(_, name, _)| name == section)
+        {
+            new_writer.rules.push((0, name.clone(), rule.clone()));
+        } else {
+            return None;
         }
-        None
+        if let Some(metadata) = &self.metadata {
+            new_writer.set_metadata(metadata.clone());
+        }
+        new_writer.modifiers = self.modifiers.clone();
+        Some(new_writer)
     }
 }
 
+/// Produces outputs from key sequences by rule rather than from a table
+pub trait KbdRule {
+    /// Every key sequence the rule accepts
+    fn inputs(&self) -> Vec<String>;
+    /// The output of an accepted key sequence
+    fn apply(&self, seq: &str) -> Option<String>;
+}
+
 #[derive(Clone)]
 pub struct KbdMap {
     keymap: BTreeMap<String, String>,
@@ -131,7 +276,86 @@ impl KbdMap {
         }
     }
 
+    /// Enumerates every key sequence of a rule
+    pub fn from_rule(rule: &dyn KbdRule) -> Self {
+        let mut map = KbdMap::new();
+        for seq in rule.inputs() {
+            if let Some(mapped) = rule.apply(&seq) {
+                map.add(seq, mapped);
+            }
+        }
+        map
+    }
+
     pub fn iter(&self) -> Iter<'_, String, String> {
         self.keymap.iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Doubled;
+
+    impl KbdRule for Doubled {
+        fn inputs(&self) -> Vec<String> {
+            vec!["a".to_string(), "b".to_string(), "c".to_string()]
+        }
+        fn apply(&self, seq: &str) -> Option<String> {
+            match seq {
+                "c" => None,
+                _ => Some(seq.repeat(2)),
+            }
+        }
+    }
+
+    #[test]
+    fn from_rule() {
+        let map = KbdMap::from_rule(&Doubled);
+        let mapped: Vec<(&String, &String)> = map.iter().collect();
+        assert_eq!(
+            mapped,
+            vec![
+                (&"a".to_string(), &"aa".to_string()),
+                (&"b".to_string(), &"bb".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn lazy_rule() {
+        let mut kbd = KbdWriter::new();
+        kbd.write_section("first".to_string(), KbdMap::new());
+        kbd.write_rule("doubled".to_string(), Doubled);
+        kbd.write_section("last".to_string(), KbdMap::new());
+        assert_eq!(kbd.section_names(), vec!["first", "doubled", "last"]);
+        assert_eq!(kbd.sections.len(), 2);
+        assert_eq!(kbd.apply_rules("b"), Some("bb".to_string()));
+        assert_eq!(kbd.apply_rules("c"), None);
+
+        let sub = kbd.subsection_writer(&"doubled".to_string()).unwrap();
+        assert_eq!(sub.apply_rules("a"), Some("aa".to_string()));
+
+        kbd.enumerate_rules();
+        let names: Vec<&String> = kbd.sections.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["first", "doubled", "last"]);
+        assert_eq!(kbd.sections[1].1.iter().count(), 2);
+        assert_eq!(kbd.apply_rules("a"), None);
+    }
+
+    #[test]
+    fn normalize() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("a;".to_string(), "ά".to_string());
+        map.add("ff".to_string(), "ﬀ".to_string());
+        kbd.write_section("test".to_string(), map);
+        kbd.normalize(Normalization::Nfd);
+        let mapped: Vec<&String> = kbd.sections[0].1.iter().map(|(_, m)| m).collect();
+        assert_eq!(mapped, vec!["\u{3b1}\u{301}", "ﬀ"]);
+        kbd.normalize(Normalization::Nfkc);
+        let mapped: Vec<&String> = kbd.sections[0].1.iter().map(|(_, m)| m).collect();
+        assert_eq!(mapped, vec!["\u{3ac}", "ff"]);
+    }
+}
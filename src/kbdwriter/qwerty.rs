@@ -0,0 +1,151 @@
+/// A physical key on a US QWERTY keyboard
+pub struct PhysicalKey {
+    pub scancode: u8,
+    pub virtual_key: &'static str,
+    pub base: char,
+    pub shifted: char,
+}
+
+const fn key(scancode: u8, virtual_key: &'static str, base: char, shifted: char) -> PhysicalKey {
+    PhysicalKey {
+        scancode,
+        virtual_key,
+        base,
+        shifted,
+    }
+}
+
+/// Character-producing keys of the US QWERTY layout, row by row
+pub const KEYS: [PhysicalKey; 48] = [
+    // number row
+    key(0x29, "OEM_3", '`', '~'),
+    key(0x02, "1", '1', '!'),
+    key(0x03, "2", '2', '@'),
+    key(0x04, "3", '3', '#'),
+    key(0x05, "4", '4', '$'),
+    key(0x06, "5", '5', '%'),
+    key(0x07, "6", '6', '^'),
+    key(0x08, "7", '7', '&'),
+    key(0x09, "8", '8', '*'),
+    key(0x0a, "9", '9', '('),
+    key(0x0b, "0", '0', ')'),
+    key(0x0c, "OEM_MINUS", '-', '_'),
+    key(0x0d, "OEM_PLUS", '=', '+'),
+    // top row
+    key(0x10, "Q", 'q', 'Q'),
+    key(0x11, "W", 'w', 'W'),
+    key(0x12, "E", 'e', 'E'),
+    key(0x13, "R", 'r', 'R'),
+    key(0x14, "T", 't', 'T'),
+    key(0x15, "Y", 'y', 'Y'),
+    key(0x16, "U", 'u', 'U'),
+    key(0x17, "I", 'i', 'I'),
+    key(0x18, "O", 'o', 'O'),
+    key(0x19, "P", 'p', 'P'),
+    key(0x1a, "OEM_4", '[', '{'),
+    key(0x1b, "OEM_6", ']', '}'),
+    key(0x2b, "OEM_5", '\\', '|'),
+    // home row
+    key(0x1e, "A", 'a', 'A'),
+    key(0x1f, "S", 's', 'S'),
+    key(0x20, "D", 'd', 'D'),
+    key(0x21, "F", 'f', 'F'),
+    key(0x22, "G", 'g', 'G'),
+    key(0x23, "H", 'h', 'H'),
+    key(0x24, "J", 'j', 'J'),
+    key(0x25, "K", 'k', 'K'),
+    key(0x26, "L", 'l', 'L'),
+    key(0x27, "OEM_1", ';', ':'),
+    key(0x28, "OEM_7", '\'', '\"'),
+    // bottom row
+    key(0x2c, "Z", 'z', 'Z'),
+    key(0x2d, "X", 'x', 'X'),
+    key(0x2e, "C", 'c', 'C'),
+    key(0x2f, "V", 'v', 'V'),
+    key(0x30, "B", 'b', 'B'),
+    key(0x31, "N", 'n', 'N'),
+    key(0x32, "M", 'm', 'M'),
+    key(0x33, "OEM_COMMA", ',', '<'),
+    key(0x34, "OEM_PERIOD", '.', '>'),
+    key(0x35, "OEM_2", '/', '?'),
+    // space bar
+    key(0x39, "SPACE", ' ', ' '),
+];
+
+/// Finds the key that types `c`, and whether shift is held to do so
+pub fn find(c: char) -> Option<(&'static PhysicalKey, bool)> {
+    KEYS.iter().find_map(|key| {
+        if key.base == c {
+            Some((key, false))
+        } else if key.shifted == c {
+            Some((key, true))
+        } else {
+            None
+        }
+    })
+}
+
+/// A key on a keyboard diagram, modifier and editing keys have no `PhysicalKey`
+pub struct KeyCap {
+    pub key: Option<&'static PhysicalKey>,
+    pub label: &'static str,
+    pub width: f32,
+}
+
+fn cap(scancode: u8, width: f32) -> KeyCap {
+    KeyCap {
+        key: KEYS.iter().find(|key| key.scancode == scancode),
+        label: "",
+        width,
+    }
+}
+
+fn blank(label: &'static str, width: f32) -> KeyCap {
+    KeyCap {
+        key: None,
+        label,
+        width,
+    }
+}
+
+/// Rows of an ANSI or ISO keyboard, widths are in units of one letter key
+pub fn rows(iso: bool) -> Vec<Vec<KeyCap>> {
+    let mut number_row: Vec<KeyCap> = std::iter::once(0x29)
+        .chain(0x02..=0x0d)
+        .map(|scancode| cap(scancode, 1.0))
+        .collect();
+    number_row.push(blank("Backspace", 2.0));
+
+    let mut top_row = vec![blank("Tab", 1.5)];
+    top_row.extend((0x10..=0x1b).map(|scancode| cap(scancode, 1.0)));
+
+    let mut home_row = vec![blank("Caps Lock", 1.75)];
+    home_row.extend((0x1e..=0x28).map(|scancode| cap(scancode, 1.0)));
+
+    let mut bottom_row = vec![];
+    if iso {
+        top_row.push(blank("Enter", 1.5));
+        home_row.push(cap(0x2b, 1.0));
+        home_row.push(blank("", 1.25));
+        bottom_row.push(blank("Shift", 1.25));
+        bottom_row.push(blank("", 1.0));
+    } else {
+        top_row.push(cap(0x2b, 1.5));
+        home_row.push(blank("Enter", 2.25));
+        bottom_row.push(blank("Shift", 2.25));
+    }
+    bottom_row.extend((0x2c..=0x35).map(|scancode| cap(scancode, 1.0)));
+    bottom_row.push(blank("Shift", 2.75));
+
+    let space_row = vec![
+        blank("Ctrl", 1.25),
+        blank("Win", 1.25),
+        blank("Alt", 1.25),
+        cap(0x39, 6.25),
+        blank("Alt", 1.25),
+        blank("Win", 1.25),
+        blank("Menu", 1.25),
+        blank("Ctrl", 1.25),
+    ];
+    vec![number_row, top_row, home_row, bottom_row, space_row]
+}
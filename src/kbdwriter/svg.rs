@@ -0,0 +1,190 @@
+use std::{cell::RefCell, collections::BTreeMap, fmt::Display};
+
+use super::{html::escape, qwerty, KbdMetaData, KbdWriter};
+
+/// Size of one letter key in pixels
+const UNIT: f32 = 60.0;
+const MARGIN: f32 = 10.0;
+const HEADER: f32 = 50.0;
+
+pub struct SvgKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+    iso: bool,
+}
+
+impl<'a> SvgKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter, iso: bool) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+            iso,
+        }
+    }
+}
+
+const STYLE: &str = r#"text { font-family: "GFS Didot", "Gentium Plus", "Noto Serif", "DejaVu Sans", serif; }
+.key { fill: #f8f8f8; stroke: #555; }
+.blank { fill: #e0e0e0; stroke: #555; }
+.title { font-size: 18px; }
+.description { font-size: 12px; fill: #444; }
+.glyph { font-size: 18px; }
+.modifier { font-size: 7px; fill: #b03000; }
+.legend { font-size: 9px; fill: #888; }
+.label { font-size: 9px; fill: #555; }"#;
+
+impl<'a> Display for SvgKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        // what each key produces on its own
+        let mut singles: BTreeMap<char, String> = BTreeMap::new();
+        for (_, keymap) in kbd.sections.iter() {
+            for (seq, mapped) in keymap.iter() {
+                let mut chars = seq.chars();
+                if let (Some(key), None) = (chars.next(), chars.next()) {
+                    singles.entry(key).or_insert_with(|| mapped.clone());
+                }
+            }
+        }
+
+        // LANGUAGE DEFINE
+        let rows = qwerty::rows(self.iso);
+        let width = 15.0 * UNIT + 2.0 * MARGIN;
+        let height = rows.len() as f32 * UNIT + HEADER + MARGIN;
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            width, height, width, height
+        )?;
+        writeln!(f, "<style>\n{}\n</style>", STYLE)?;
+        writeln!(
+            f,
+            "<text class=\"title\" x=\"{}\" y=\"24\">{} ({})</text>",
+            MARGIN,
+            escape(&metadata.language),
+            escape(&metadata.language_code)
+        )?;
+        writeln!(
+            f,
+            "<text class=\"description\" x=\"{}\" y=\"40\">{}</text>",
+            MARGIN,
+            escape(&metadata.description)
+        )?;
+
+        // KEYMAP
+        for (row_num, row) in rows.iter().enumerate() {
+            let mut x = MARGIN;
+            let y = HEADER + row_num as f32 * UNIT;
+            for keycap in row.iter() {
+                let key_width = keycap.width * UNIT - 4.0;
+                writeln!(f, "<g transform=\"translate({},{})\">", x + 2.0, y + 2.0)?;
+                match keycap.key {
+                    None => {
+                        writeln!(
+                            f,
+                            "  <rect class=\"blank\" width=\"{}\" height=\"{}\" rx=\"4\"/>",
+                            key_width,
+                            UNIT - 4.0
+                        )?;
+                        writeln!(
+                            f,
+                            "  <text class=\"label\" x=\"6\" y=\"{}\">{}</text>",
+                            UNIT - 10.0,
+                            keycap.label
+                        )?;
+                    }
+                    Some(key) => {
+                        writeln!(
+                            f,
+                            "  <rect class=\"key\" width=\"{}\" height=\"{}\" rx=\"4\"/>",
+                            key_width,
+                            UNIT - 4.0
+                        )?;
+                        // physical key legend
+                        let legend = match key.base.to_uppercase().eq(key.shifted.to_uppercase()) {
+                            true => key.shifted.to_string(),
+                            false => format!("{} {}", key.shifted, key.base),
+                        };
+                        writeln!(
+                            f,
+                            "  <text class=\"legend\" x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+                            key_width - 4.0,
+                            UNIT - 8.0,
+                            escape(legend.trim())
+                        )?;
+                        // shifted on the top half, base on the bottom half
+                        for (c, top) in [(key.shifted, 0.0), (key.base, UNIT / 2.0 - 4.0)] {
+                            // rules are not expanded for the diagram, they are asked for the key
+                            let mapped = singles
+                                .get(&c)
+                                .cloned()
+                                .or_else(|| kbd.apply_rules(&c.to_string()));
+                            if let Some(mapped) = mapped {
+                                writeln!(
+                                    f,
+                                    "  <text class=\"glyph\" x=\"6\" y=\"{}\">{}</text>",
+                                    top + 22.0,
+                                    escape(&mapped)
+                                )?;
+                            }
+                            if let Some(modifier) = kbd.modifiers.get(&c) {
+                                writeln!(
+                                    f,
+                                    "  <text class=\"modifier\" x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+                                    key_width - 4.0,
+                                    top + 11.0,
+                                    escape(modifier)
+                                )?;
+                            }
+                            // the space bar types the same character with shift
+                            if key.base == key.shifted {
+                                break;
+                            }
+                        }
+                    }
+                }
+                writeln!(f, "</g>")?;
+                x += keycap.width * UNIT;
+            }
+        }
+        // footer
+        writeln!(f, "</svg>")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn keys() {
+        let mut kbd = KbdWriter::new();
+        let mut map = KbdMap::new();
+        map.add("h".to_string(), "η".to_string());
+        map.add("H".to_string(), "Η".to_string());
+        map.add("a;".to_string(), "ά".to_string());
+        kbd.write_section("test".to_string(), map);
+        kbd.add_modifier(';', "acute".to_string());
+        kbd.add_modifier(':', "smooth".to_string());
+        let output = SvgKbdWriter::new(&kbd, false).to_string();
+        assert!(output.contains("<text class=\"glyph\" x=\"6\" y=\"22\">Η</text>"));
+        assert!(output.contains("<text class=\"glyph\" x=\"6\" y=\"48\">η</text>"));
+        assert!(output.contains("text-anchor=\"end\">acute</text>"));
+        assert!(output.contains("text-anchor=\"end\">smooth</text>"));
+        assert!(!output.contains(">ά<"));
+    }
+    #[test]
+    fn rows_are_full_width() {
+        for iso in [false, true] {
+            for row in qwerty::rows(iso) {
+                let width: f32 = row.iter().map(|keycap| keycap.width).sum();
+                assert_eq!(width, 15.0);
+            }
+        }
+    }
+}
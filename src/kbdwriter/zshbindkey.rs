@@ -0,0 +1,131 @@
+use std::{cell::RefCell, collections::BTreeSet, fmt::Display};
+
+use super::{KbdMetaData, KbdWriter};
+
+pub struct ZshBindkeyKbdWriter<'a> {
+    kbd: RefCell<&'a KbdWriter>,
+}
+
+impl<'a> ZshBindkeyKbdWriter<'a> {
+    pub fn new(kbd: &'a KbdWriter) -> Self {
+        Self {
+            kbd: RefCell::new(kbd),
+        }
+    }
+
+    /// Mappings left out because zsh types their output as keys, which would map it again
+    pub fn dropped(&self) -> Vec<(String, String)> {
+        self.kbd.borrow().retyped()
+    }
+}
+
+/// Escapes a string for bindkey and quotes it for the shell
+fn quote(str: &str) -> String {
+    let mut quoted = String::from('\'');
+    for c in str.chars() {
+        match c {
+            '\\' | '^' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\'' => quoted.push_str("'\\''"),
+            c if c.is_ascii_control() => quoted.push_str(&format!("\\{:03o}", c as u32)),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+impl<'a> Display for ZshBindkeyKbdWriter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kbd = self.kbd.borrow();
+
+        let metadata = match kbd.metadata.is_some() {
+            true => kbd.metadata.as_ref().unwrap().clone(),
+            false => KbdMetaData::default(),
+        };
+        let keymap_name = metadata.identifier();
+        let toggle = format!("{}-toggle", keymap_name);
+        let retyped = self.dropped();
+        let dropped: BTreeSet<(&str, &str)> = retyped
+            .iter()
+            .map(|(seq, mapped)| (seq.as_str(), mapped.as_str()))
+            .collect();
+
+        // Comment
+        writeln!(f, "# {} ({})", metadata.language, metadata.language_code)?;
+        writeln!(f, "# {}", metadata.description)?;
+        writeln!(
+            f,
+            "# Bound in the {} keymap, Ctrl-X Ctrl-K toggles it for the line being edited",
+            keymap_name
+        )?;
+        writeln!(f, "bindkey -N {} main", keymap_name)?;
+
+        // KEYMAP
+        // sections
+        for (section, keymap) in kbd.sections.iter() {
+            writeln!(f, "# {}", section)?;
+            for (seq, mapped) in keymap.iter() {
+                if dropped.contains(&(seq.as_str(), mapped.as_str())) {
+                    continue;
+                }
+                writeln!(
+                    f,
+                    "bindkey -M {} -s {} {}",
+                    keymap_name,
+                    quote(seq),
+                    quote(mapped)
+                )?;
+            }
+        }
+
+        // TOGGLE
+        writeln!(f, "{}() {{", toggle)?;
+        writeln!(f, "    if [[ $KEYMAP == {} ]]; then", keymap_name)?;
+        writeln!(f, "        zle -K main")?;
+        writeln!(f, "    else")?;
+        writeln!(f, "        zle -K {}", keymap_name)?;
+        writeln!(f, "    fi")?;
+        writeln!(f, "}}")?;
+        writeln!(f, "zle -N {}", toggle)?;
+        writeln!(f, "bindkey -M main '^X^K' {}", toggle)?;
+        writeln!(f, "bindkey -M {} '^X^K' {}", keymap_name, toggle)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kbdwriter::KbdMap;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("a\\"), "'a\\\\'");
+        assert_eq!(quote("^'"), "'\\^'\\'''");
+        assert_eq!(quote("\u{1b}"), "'\\033'");
+        assert_eq!(quote("\"ς "), "'\"ς '");
+    }
+
+    #[test]
+    fn keymap() {
+        let mut kbd = KbdWriter::new();
+        kbd.set_metadata(KbdMetaData::new(
+            "Esperanto".to_string(),
+            "eo".to_string(),
+            "".to_string(),
+        ));
+        let mut map = KbdMap::new();
+        map.add("cx".to_string(), "ĉ".to_string());
+        map.add("cxx".to_string(), "cx".to_string());
+        kbd.write_section("x-system".to_string(), map);
+        let output = ZshBindkeyKbdWriter::new(&kbd).to_string();
+        assert!(output.contains("bindkey -N esperanto main\n"));
+        assert!(output.contains("bindkey -M esperanto -s 'cx' 'ĉ'\n"));
+        // cx would be typed as keys and turn into ĉ again
+        assert!(!output.contains("'cxx'"));
+        assert!(output.contains("bindkey -M main '^X^K' esperanto-toggle\n"));
+    }
+}
@@ -1,14 +1,30 @@
 use std::{fmt::Write, io::BufRead, process::exit};
 
-use automaton::Automaton;
+use automaton::{Automaton, AutomatonStyle, RankDir, StylePreset};
 use clap::Parser;
 use convert_case::{Case, Casing};
 
 use kbdlayout::Layout;
-use kbdwriter::{graphviz::GraphVizKbdWriter, Format, KbdWriter};
+use kbdwriter::{graphviz::GraphVizKbdWriter, Format, KbdWriter, Normalization};
 
-use crate::kbdwriter::{emacs::EmacsKbdWriter, list::ListKbdWriter, vimfn::VimFnKbdWriter};
+use crate::kbdwriter::{
+    autohotkey::AutohotkeyKbdWriter,
+    chromeime::ChromeImeKbdWriter,
+    emacs::EmacsKbdWriter,
+    espanso::EspansoKbdWriter,
+    html::HtmlKbdWriter,
+    inputrc::InputrcKbdWriter,
+    klc::{self, KlcKbdWriter},
+    latex::LatexKbdWriter,
+    list::ListKbdWriter,
+    markdown::MarkdownKbdWriter,
+    mermaid::MermaidKbdWriter,
+    svg::SvgKbdWriter,
+    vimfn::VimFnKbdWriter,
+    zshbindkey::ZshBindkeyKbdWriter,
+};
 
+mod audit;
 mod automaton;
 mod compose;
 mod kbdlayout;
@@ -21,6 +37,7 @@ enum Command {
     ListSubgraphs(ListSubgraphArgs),
     Generate(GenArgs),
     Automaton(AutomatonArgs),
+    Audit(AuditArgs),
 }
 
 #[derive(Parser, Default, Debug)]
@@ -29,6 +46,13 @@ struct ListSubgraphArgs {
     layout: Option<Layout>,
 }
 #[derive(Parser, Default, Debug)]
+struct AuditArgs {
+    #[clap(short, long, arg_enum, required = true)]
+    layout: Option<Layout>,
+    #[clap(short, long)]
+    subgraph: Option<String>,
+}
+#[derive(Parser, Default, Debug)]
 struct GenArgs {
     #[clap(short, long, arg_enum, required = true)]
     layout: Option<Layout>,
@@ -38,6 +62,30 @@ struct GenArgs {
     subgraph: Option<String>,
     #[clap(short, long, value_hint = clap::ValueHint::FilePath)]
     output_file: Option<std::path::PathBuf>,
+    /// Draw an ISO rather than an ANSI keyboard in the svg format
+    #[clap(long)]
+    iso: bool,
+    /// Style preset for the graph-viz format
+    #[clap(long, arg_enum, default_value = "light")]
+    style: StylePreset,
+    /// Style file for the graph-viz format, one `field = value` per line
+    #[clap(long, value_hint = clap::ValueHint::FilePath)]
+    style_file: Option<std::path::PathBuf>,
+    /// Override one graph-viz style field, as `field=value`
+    #[clap(long, multiple_occurrences = true)]
+    style_set: Vec<String>,
+    /// Rank direction of the graph-viz format
+    #[clap(long, arg_enum)]
+    rankdir: Option<RankDir>,
+    /// Hide intermediate states in the graph-viz format, labelling edges with whole sequences
+    #[clap(long)]
+    collapse_intermediate: bool,
+    /// Fail if any output still contains combining marks after composition
+    #[clap(long)]
+    strict: bool,
+    /// Normalize every output to this form before formatting
+    #[clap(long, arg_enum)]
+    normalization: Option<Normalization>,
 }
 
 #[derive(Parser, Debug, Default)]
@@ -58,6 +106,24 @@ struct AutomatonArgs {
     layout: Option<Layout>,
     #[clap(short, long)]
     subgraph: Option<String>,
+    /// Normalize every output to this form before building the automaton
+    #[clap(long, arg_enum)]
+    normalization: Option<Normalization>,
+}
+
+/// Warns about mappings a writer cannot express, `reason` completes "N mappings ..."
+fn warn_dropped(dropped: &[(String, String)], reason: &str) {
+    if dropped.is_empty() {
+        return;
+    }
+    eprintln!(
+        "Warning: {} mappings {} and were dropped:",
+        dropped.len(),
+        reason
+    );
+    for (seq, mapped) in dropped.iter() {
+        eprintln!("    〈{}〉 → 〈{}〉", seq, mapped);
+    }
 }
 
 fn main() {
@@ -86,34 +152,14 @@ fn main() {
         }
         Command::ListSubgraphs(args) => {
             let mut kbd = KbdWriter::new();
-            match args.layout.unwrap() {
-                Layout::Greek => {
-                    kbdlayout::greek::gen(&mut kbd);
-                }
-                Layout::Latin => {
-                    kbdlayout::latin::gen(&mut kbd, true);
-                }
-                Layout::Russian => {
-                    kbdlayout::russian::gen(&mut kbd);
-                }
-            }
-            for (name, _) in kbd.sections.iter() {
+            args.layout.unwrap().gen(&mut kbd);
+            for name in kbd.section_names() {
                 println!("{}", name);
             }
         }
         Command::Generate(args) => {
             let mut kbd = KbdWriter::new();
-            match args.layout.unwrap() {
-                Layout::Greek => {
-                    kbdlayout::greek::gen(&mut kbd);
-                }
-                Layout::Latin => {
-                    kbdlayout::latin::gen(&mut kbd, true);
-                }
-                Layout::Russian => {
-                    kbdlayout::russian::gen(&mut kbd);
-                }
-            }
+            args.layout.unwrap().gen(&mut kbd);
             // subgraph
             if let Some(section) = args.subgraph {
                 if let Some(sub_writer) = kbd.subsection_writer(&section) {
@@ -124,8 +170,32 @@ fn main() {
                     exit(-1);
                 }
             }
+            // the formats listing every key sequence list those of the rules too
+            if args.format.lists_sequences() {
+                kbd.enumerate_rules();
+            }
+            if let Some(form) = args.normalization {
+                kbd.normalize(form);
+            }
+            // strict composition
+            if args.strict {
+                let mut loose = 0;
+                for (section, keymap) in kbd.sections.iter() {
+                    for (seq, mapped) in keymap.iter() {
+                        if let Err(e) = compose::compose_strict(mapped.chars().collect()) {
+                            eprintln!("{}: 〈{}〉 → 〈{}〉: {}", section, seq, mapped, e);
+                            loose += 1;
+                        }
+                    }
+                }
+                if loose > 0 {
+                    eprintln!("{} mappings contain loose combining marks.", loose);
+                    exit(-1);
+                }
+            }
             // write output
             let mut output = String::new();
+            let mut package: Vec<(&str, String)> = Vec::new();
             match args.format {
                 Format::List => {
                     let map = ListKbdWriter::new(&kbd);
@@ -140,29 +210,105 @@ fn main() {
                     output.write_fmt(format_args!("{}", map)).expect("");
                 }
                 Format::GraphViz => {
-                    let map = GraphVizKbdWriter::new(&kbd);
+                    let mut style = AutomatonStyle::from_preset(args.style);
+                    if let Some(rankdir) = args.rankdir {
+                        style.set_rankdir(rankdir);
+                    }
+                    if let Some(style_file) = args.style_file {
+                        let contents =
+                            std::fs::read_to_string(style_file).expect("Unable to read style file");
+                        if let Err(e) = style.set_from_file(&contents) {
+                            eprintln!("Invalid style file: {}", e);
+                            exit(-1);
+                        }
+                    }
+                    for setting in args.style_set.iter() {
+                        if let Err(e) = style.set(setting) {
+                            eprintln!("Invalid style: {}", e);
+                            exit(-1);
+                        }
+                    }
+                    let map = GraphVizKbdWriter::new(&kbd, style, args.collapse_intermediate);
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Klc => {
+                    let map = KlcKbdWriter::new(&kbd);
+                    warn_dropped(&map.dropped(), "cannot be expressed as dead keys");
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Autohotkey => {
+                    let map = AutohotkeyKbdWriter::new(&kbd);
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Espanso => {
+                    let map = EspansoKbdWriter::new(&kbd);
+                    warn_dropped(&map.dropped(), "start with a shorter trigger");
+                    package = map.files();
+                }
+                Format::ChromeIme => {
+                    package = ChromeImeKbdWriter::new(&kbd).files();
+                }
+                Format::Inputrc => {
+                    let map = InputrcKbdWriter::new(&kbd);
+                    warn_dropped(&map.dropped(), "output keys that start a sequence");
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::ZshBindkey => {
+                    let map = ZshBindkeyKbdWriter::new(&kbd);
+                    warn_dropped(&map.dropped(), "output keys that start a sequence");
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Html => {
+                    let map = HtmlKbdWriter::new(&kbd);
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Svg => {
+                    let map = SvgKbdWriter::new(&kbd, args.iso);
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Markdown => {
+                    let map = MarkdownKbdWriter::new(&kbd);
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Latex => {
+                    let map = LatexKbdWriter::new(&kbd);
+                    output.write_fmt(format_args!("{}", map)).expect("");
+                }
+                Format::Mermaid => {
+                    let map = MermaidKbdWriter::new(&kbd);
                     output.write_fmt(format_args!("{}", map)).expect("");
                 }
             }
-            if let Some(ofile) = args.output_file {
-                std::fs::write(ofile, &output).expect("Unable to write file");
+            if !package.is_empty() {
+                // multi-file formats are written to a directory
+                if let Some(dir) = args.output_file {
+                    std::fs::create_dir_all(&dir).expect("Unable to create directory");
+                    for (name, contents) in package.iter() {
+                        std::fs::write(dir.join(name), contents).expect("Unable to write file");
+                    }
+                } else {
+                    for (name, contents) in package.iter() {
+                        println!("==> {} <==", name);
+                        println!("{}", contents);
+                    }
+                }
+            } else if let Some(ofile) = args.output_file {
+                if args.format == Format::Klc {
+                    std::fs::write(ofile, klc::encode_utf16(&output))
+                        .expect("Unable to write file");
+                } else {
+                    std::fs::write(ofile, &output).expect("Unable to write file");
+                }
+            } else if args.format == Format::Klc {
+                std::io::Write::write_all(&mut std::io::stdout(), &klc::encode_utf16(&output))
+                    .expect("Unable to write to stdout");
             } else {
                 println!("{}", output);
             }
         }
         Command::Automaton(args) => {
             let mut kbd = KbdWriter::new();
-            match args.layout.unwrap() {
-                Layout::Greek => {
-                    kbdlayout::greek::gen(&mut kbd);
-                }
-                Layout::Latin => {
-                    kbdlayout::latin::gen(&mut kbd, true);
-                }
-                Layout::Russian => {
-                    kbdlayout::russian::gen(&mut kbd);
-                }
-            }
+            args.layout.unwrap().gen(&mut kbd);
             if let Some(section) = args.subgraph {
                 if let Some(sub_writer) = kbd.subsection_writer(&section) {
                     eprintln!("Found subgraph: '{}'", section);
@@ -172,6 +318,13 @@ fn main() {
                     exit(-1);
                 }
             }
+            // the generated code cannot apply a rule
+            if matches!(args.command, AutomatonCommand::CodeGen) {
+                kbd.enumerate_rules();
+            }
+            if let Some(form) = args.normalization {
+                kbd.normalize(form);
+            }
 
             let automaton = Automaton::from_writer(&kbd).0;
 
@@ -182,7 +335,9 @@ fn main() {
                     for line in stdin.lock().lines() {
                         match line {
                             Ok(l) => {
-                                if let Some(mapped) = automaton.run(&l) {
+                                if let Some(mapped) =
+                                    automaton.run(&l).or_else(|| kbd.apply_rules(&l))
+                                {
                                     println!("Found match: {}", mapped);
                                 } else {
                                     println!("No match!");
@@ -193,7 +348,7 @@ fn main() {
                     }
                 }
                 AutomatonCommand::Test { test } => {
-                    if let Some(mapped) = automaton.run(&test) {
+                    if let Some(mapped) = automaton.run(&test).or_else(|| kbd.apply_rules(&test)) {
                         println!("Found match: {}", mapped);
                     } else {
                         println!("No match!");
@@ -208,5 +363,40 @@ fn main() {
                 }
             }
         }
+        Command::Audit(args) => {
+            let layout = args.layout.unwrap();
+            let mut kbd = KbdWriter::new();
+            layout.gen(&mut kbd);
+            if let Some(section) = args.subgraph {
+                if let Some(sub_writer) = kbd.subsection_writer(&section) {
+                    eprintln!("Found subgraph: '{}'", section);
+                    kbd = sub_writer;
+                } else {
+                    eprintln!("Invalid subgraph.");
+                    exit(-1);
+                }
+            }
+
+            kbd.enumerate_rules();
+
+            // summary per section
+            let mut total = 0;
+            for report in audit::audit(&kbd, layout.extra_scripts()) {
+                println!(
+                    "{}: {} mappings, {} issues",
+                    report.section,
+                    report.mappings,
+                    report.issues.len()
+                );
+                for (seq, mapped, issue) in report.issues.iter() {
+                    println!("    〈{}〉 → 〈{}〉: {}", seq, mapped, issue);
+                }
+                total += report.issues.len();
+            }
+            if total > 0 {
+                eprintln!("Found {} issues.", total);
+                exit(-1);
+            }
+        }
     }
 }